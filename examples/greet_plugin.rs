@@ -0,0 +1,73 @@
+//! Example of an addon built as a plugin, loaded by my_tools from its plugins directory
+//!
+//! Build it with "cargo build --example greet_plugin", then copy the library of target/debug/examples
+//! (e.g. libgreet_plugin.so) to the plugins directory (see "my_tools --help").
+
+use my_tools::*;
+
+/// Command to greet someone, a few times
+struct GreetCommand {
+    name: String,
+    times: u8,
+}
+
+impl MyToolsAddonCommand for GreetCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let greetings: Vec<String> = (0..self.times).map(|_| format!("Greetings, {}!", self.name)).collect();
+        Ok(CommandResult::with_text(greetings.clone().into(), greetings.join("\n")))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("", "Greet someone", |args| Ok(Box::new(GreetCommand {
+                name: args.arg("name").to_string(),
+                times: args.number("times")?.unwrap_or(1),
+            })))
+            .arg(ArgSpec::required("name", ValueType::Text))
+            .option(OptionSpec::value("times", "N", ValueType::Unsigned, "Number of greetings (default: 1)"))
+        ]
+    }
+}
+
+/// Command to answer whether a name is polite, negatively if it is shouted
+struct PoliteCommand {
+    name: String,
+}
+
+impl MyToolsAddonCommand for PoliteCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        match self.name.chars().any(char::is_lowercase) {
+            true => Ok(CommandResult::with_text(true.into(), "yes")),
+            false => Err(MyToolsError::NegativeResult(CommandResult::with_text(false.into(), "no"))),
+        }
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("polite", "Check if a name is not shouted", |args| Ok(Box::new(PoliteCommand {
+                name: args.arg("name").to_string(),
+            })))
+            .arg(ArgSpec::required("name", ValueType::Text))
+        ]
+    }
+}
+
+/// GreetAddon structure
+pub struct GreetAddon;
+
+impl MyToolsAddon for GreetAddon {
+    /// Get the keyword of the addon
+    fn get_keyword(&self) -> &'static str {
+        "greet"
+    }
+
+    /// Get the specs of the commands
+    fn get_command_specs(&self) -> Vec<CommandSpec> {
+        [
+            PoliteCommand::get_command_specs(),
+            GreetCommand::get_command_specs(),
+        ].concat()
+    }
+}
+
+export_addon!(GreetAddon);
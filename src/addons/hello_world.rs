@@ -5,18 +5,14 @@ struct HelloWorldCommand {}
 
 impl MyToolsAddonCommand for HelloWorldCommand {
     fn execute(&self) -> Result<CommandResult, MyToolsError> {
-        Ok("Hello, world!".to_string())
+        Ok(CommandResult::field("message", "Hello, world!"))
     }
 
-    fn get_command_input() -> CommandInputs {
+    fn get_command_specs() -> Vec<CommandSpec> {
         vec![
-            "".to_string()
+            CommandSpec::new("", "Print \"Hello, world!\"", |_| Ok(Box::new(HelloWorldCommand {})))
         ]
     }
-
-    fn get_command_help() -> CommandHelp {
-        "Print \"Hello, world!\"".to_string()
-    }
 }
 
 /// Command to print "Hello, <name>!"
@@ -26,18 +22,17 @@ struct HelloInputCommand {
 
 impl MyToolsAddonCommand for HelloInputCommand {
     fn execute(&self) -> Result<CommandResult, MyToolsError> {
-        Ok(format!("Hello, {}!", self.name))
+        Ok(CommandResult::field("message", format!("Hello, {}!", self.name)))
     }
 
-    fn get_command_input() -> CommandInputs {
+    fn get_command_specs() -> Vec<CommandSpec> {
         vec![
-            "<name>".to_string()
+            CommandSpec::new("", "Print \"Hello, <name>!\"", |args| Ok(Box::new(HelloInputCommand {
+                name: args.arg("name").to_string(),
+            })))
+            .arg(ArgSpec::required("name", ValueType::Text))
         ]
     }
-
-    fn get_command_help() -> CommandHelp {
-        "Print \"Hello, <name>!\"".to_string()
-    }
 }
 
 
@@ -50,37 +45,12 @@ impl MyToolsAddon for HelloWorldAddon {
         "hello"
     }
 
-    /// Parse the arguments and return the corresponding command
-    fn parse(&self, args: &[String]) -> Result<Box<dyn MyToolsAddonCommand>, MyToolsError> {
-        // Convert &[String] to &[&str]
-        let args: Vec<&str> = args
-            .iter()
-            .map(|s| s.as_str())
-            .collect();
-
-        // Get the help message if args correspond to "--help" or "-h"
-        self.call_help(&args);
-
-        // Parse the arguments and return the corresponding command
-        match args[..] {
-            [] => Ok(Box::new(HelloWorldCommand {})),
-            [name] => Ok(Box::new(HelloInputCommand { name: name.to_string() })),
-            _ => Err(MyToolsError::InvalidCommand(format!("Invalid command : {}\n", args.join(" "))))
-        }
-    }
-
-    /// Get the list of commands
-    fn get_list_commands(&self) -> Vec<CommandInputsHelp> {
-        vec![
-            CommandInputsHelp {
-                inputs_msg: HelloWorldCommand::get_command_input(),
-                help_msg: HelloWorldCommand::get_command_help()
-            },
-            CommandInputsHelp {
-                inputs_msg: HelloInputCommand::get_command_input(),
-                help_msg: HelloInputCommand::get_command_help()
-            },
-        ]
+    /// Get the specs of the commands
+    fn get_command_specs(&self) -> Vec<CommandSpec> {
+        [
+            HelloWorldCommand::get_command_specs(),
+            HelloInputCommand::get_command_specs(),
+        ].concat()
     }
 }
 
@@ -1,331 +1,3278 @@
 use my_tools::*;
-use std::net::Ipv4Addr;
-use ipnetwork::Ipv4Network;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use ipnetwork::IpNetwork;
+use std::str::FromStr;
+
+/// Number of addresses in ::/0 (2^128), which does not fit in a u128
+const IPV6_ADDRESS_COUNT: &str = "340282366920938463463374607431768211456";
+/// Well-known NAT64 prefix (RFC 6052)
+const NAT64_WELL_KNOWN_PREFIX: &str = "64:ff9b::/96";
+/// Default number of subnets listed by the split command
+const SPLIT_DEFAULT_LIMIT: u128 = 256;
+/// IANA IPv4 and IPv6 special-purpose address registries (RFC 6890 and updates): prefix, name, RFC
+const SPECIAL_PURPOSE_REGISTRY: &[(&str, &str, &str)] = &[
+    ("0.0.0.0/8", "This network", "RFC 791"),
+    ("0.0.0.0/32", "This host on this network", "RFC 1122"),
+    ("10.0.0.0/8", "Private-Use", "RFC 1918"),
+    ("100.64.0.0/10", "Shared Address Space (CGNAT)", "RFC 6598"),
+    ("127.0.0.0/8", "Loopback", "RFC 1122"),
+    ("169.254.0.0/16", "Link Local", "RFC 3927"),
+    ("172.16.0.0/12", "Private-Use", "RFC 1918"),
+    ("192.0.0.0/24", "IETF Protocol Assignments", "RFC 6890"),
+    ("192.0.0.0/29", "IPv4 Service Continuity Prefix", "RFC 7335"),
+    ("192.0.0.8/32", "IPv4 dummy address", "RFC 7600"),
+    ("192.0.0.9/32", "Port Control Protocol Anycast", "RFC 7723"),
+    ("192.0.0.10/32", "Traversal Using Relays around NAT Anycast", "RFC 8155"),
+    ("192.0.0.170/32", "NAT64/DNS64 Discovery", "RFC 8880"),
+    ("192.0.0.171/32", "NAT64/DNS64 Discovery", "RFC 8880"),
+    ("192.0.2.0/24", "Documentation (TEST-NET-1)", "RFC 5737"),
+    ("192.31.196.0/24", "AS112-v4", "RFC 7535"),
+    ("192.52.193.0/24", "AMT", "RFC 7450"),
+    ("192.88.99.0/24", "Deprecated (6to4 Relay Anycast)", "RFC 7526"),
+    ("192.168.0.0/16", "Private-Use", "RFC 1918"),
+    ("192.175.48.0/24", "Direct Delegation AS112 Service", "RFC 7534"),
+    ("198.18.0.0/15", "Benchmarking", "RFC 2544"),
+    ("198.51.100.0/24", "Documentation (TEST-NET-2)", "RFC 5737"),
+    ("203.0.113.0/24", "Documentation (TEST-NET-3)", "RFC 5737"),
+    ("224.0.0.0/4", "Multicast", "RFC 5771"),
+    ("240.0.0.0/4", "Reserved", "RFC 1112"),
+    ("255.255.255.255/32", "Limited Broadcast", "RFC 919"),
+    ("::/128", "Unspecified Address", "RFC 4291"),
+    ("::1/128", "Loopback Address", "RFC 4291"),
+    ("::ffff:0:0/96", "IPv4-mapped Address", "RFC 4291"),
+    ("64:ff9b::/96", "IPv4-IPv6 Translation (NAT64)", "RFC 6052"),
+    ("64:ff9b:1::/48", "IPv4-IPv6 Translation (local-use NAT64)", "RFC 8215"),
+    ("100::/64", "Discard-Only Address Block", "RFC 6666"),
+    ("2001::/23", "IETF Protocol Assignments", "RFC 2928"),
+    ("2001::/32", "Teredo", "RFC 4380"),
+    ("2001:1::1/128", "Port Control Protocol Anycast", "RFC 7723"),
+    ("2001:1::2/128", "Traversal Using Relays around NAT Anycast", "RFC 8155"),
+    ("2001:2::/48", "Benchmarking", "RFC 5180"),
+    ("2001:3::/32", "AMT", "RFC 7450"),
+    ("2001:4:112::/48", "AS112-v6", "RFC 7535"),
+    ("2001:10::/28", "Deprecated (previously ORCHID)", "RFC 4843"),
+    ("2001:20::/28", "ORCHIDv2", "RFC 7343"),
+    ("2001:db8::/32", "Documentation", "RFC 3849"),
+    ("2002::/16", "6to4", "RFC 3056"),
+    ("2620:4f:8000::/48", "Direct Delegation AS112 Service", "RFC 7534"),
+    ("3fff::/20", "Documentation", "RFC 9637"),
+    ("5f00::/16", "Segment Routing (SRv6) SIDs", "RFC 9602"),
+    ("fc00::/7", "Unique-Local (ULA)", "RFC 4193"),
+    ("fe80::/10", "Link-Local Unicast", "RFC 4291"),
+    ("ff00::/8", "Multicast", "RFC 4291"),
+];
+
+// Value types of the command arguments, parsed once by the parsers of the arguments and read back by the builders
+const ADDRESS: ValueType = ValueType::Custom(|arg| arg_to_ip(arg).map(parsed_value));
+const ADDRESS_OR_INTEGER: ValueType = ValueType::Custom(|arg| arg_to_ip_or_integer(arg).map(parsed_value));
+const IPV4: ValueType = ValueType::Custom(|arg| arg_to_ipv4(arg).map(parsed_value));
+const IPV6: ValueType = ValueType::Custom(|arg| arg_to_ipv6(arg).map(parsed_value));
+const NETWORK: ValueType = ValueType::Custom(|arg| arg_to_ipnetwork(arg, None).map(parsed_value));
+const NETWORK_OR_HOST: ValueType = ValueType::Custom(|arg| arg_to_ipnetwork_or_host(arg).map(parsed_value));
+/// Network of a list, where "-" (parsed as None) reads the networks from stdin
+const NETWORK_LIST_ITEM: ValueType = ValueType::Custom(|arg| match arg {
+    "-" => Ok(parsed_value(None::<IpNetwork>)),
+    _ => arg_to_ipnetwork_or_host(arg).map(|ip_object| parsed_value(Some(ip_object))),
+});
+/// Network of the range command, an argument without '/' being reported as an invalid range
+const RANGE_NETWORK: ValueType = ValueType::Custom(|arg| match arg.contains('/') {
+    true => arg_to_ipnetwork(arg, None).map(parsed_value),
+    false => arg_to_range(arg).and(Err(MyToolsError::ParseCommandError("Separator '/' is missing".to_string()))),
+});
+const RANGE: ValueType = ValueType::Custom(|arg| arg_to_range(arg).map(parsed_value));
+/// Prefix length or mask following an address
+const MASK: ValueType = ValueType::Custom(|arg| arg_to_mask(arg).map(parsed_value));
+/// Prefix length or contiguous netmask, the second argument of the range command
+/// (anything else is the end of a range, as are 0.0.0.0 and 255.255.255.255 which would be ambiguous masks)
+const PREFIX_OR_NETMASK: ValueType = ValueType::Custom(|arg| match arg_to_mask(arg) {
+    Ok(mask @ Mask::Prefix(_)) => Ok(parsed_value(mask)),
+    Ok(mask @ Mask::Address(netmask)) if is_netmask(&netmask) && !is_ambiguous_mask(&netmask) => Ok(parsed_value(mask)),
+    _ => Err(MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg))),
+});
+const PREFIX: ValueType = ValueType::Custom(|arg| arg_to_prefix(arg).map(parsed_value));
+const NAT64_PREFIX: ValueType = ValueType::Custom(|arg| arg_to_nat64_prefix(arg).map(parsed_value));
+const SLAAC_PREFIX: ValueType = ValueType::Custom(|arg| arg_to_slaac_prefix(arg).map(parsed_value));
+const MAC: ValueType = ValueType::Custom(|arg| arg_to_mac(arg).map(parsed_value));
+const REQUIREMENT: ValueType = ValueType::Custom(|arg| arg_to_requirement(arg).map(parsed_value));
+const TEREDO_FLAGS: ValueType = ValueType::Custom(|arg| arg_to_teredo_flags(arg).map(parsed_value));
 
 // Command to get the IP address
 struct GetIpAddressCommand {
-    ip_object: Ipv4Network,
+    ip_object: IpNetwork,
 }
 
 impl MyToolsAddonCommand for GetIpAddressCommand {
     fn execute(&self) -> Result<CommandResult, MyToolsError> {
-        Ok(format!("{}", self.ip_object.ip()))
-    }
-
-    fn get_command_input() -> CommandInputs {
-        vec![
-            "get address <ip/cidr>".to_string(),
-            "get address <ip/mask>".to_string(),
-            "get address <ip> <cidr>".to_string(),
-            "get address <ip> <mask>".to_string(),
-        ]
+        Ok(CommandResult::field("address", self.ip_object.ip().to_string()))
     }
 
-    fn get_command_help() -> CommandHelp {
-        "Get the IP address for a given IP object".to_string()
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("get address", "Get the IP address for a given IPv4 or IPv6 object", |args| {
+            Ok(Box::new(GetIpAddressCommand { ip_object: parsed_network(args)? }))
+        })
     }
 }
 
 // Command to get the netmask address
 struct GetIpNetmaskCommand {
-    ip_object: Ipv4Network,
+    ip_object: IpNetwork,
 }
 
 impl MyToolsAddonCommand for GetIpNetmaskCommand {
     fn execute(&self) -> Result<CommandResult, MyToolsError> {
-        Ok(format!("{}", self.ip_object.mask()))
-    }
-
-    fn get_command_input() -> CommandInputs {
-        vec![
-            "get netmask <ip/cidr>".to_string(),
-            "get netmask <ip/mask>".to_string(),
-            "get netmask <ip> <cidr>".to_string(),
-            "get netmask <ip> <mask>".to_string(),
-        ]
+        Ok(CommandResult::field("netmask", self.ip_object.mask().to_string()))
     }
 
-    fn get_command_help() -> CommandHelp {
-        "Get the IP netmask for a given IP object".to_string()
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("get netmask", "Get the IP netmask for a given IPv4 or IPv6 object", |args| {
+            Ok(Box::new(GetIpNetmaskCommand { ip_object: parsed_network(args)? }))
+        })
     }
 }
 
 // Command to get the network address
 struct GetIpNetworkCommand {
-    ip_object: Ipv4Network,
+    ip_object: IpNetwork,
 }
 
 impl MyToolsAddonCommand for GetIpNetworkCommand {
     fn execute(&self) -> Result<CommandResult, MyToolsError> {
-        Ok(format!("{}", self.ip_object.network()))
+        Ok(CommandResult::field("network", self.ip_object.network().to_string()))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("get network", "Get the IP network address for a given IPv4 or IPv6 object", |args| {
+            Ok(Box::new(GetIpNetworkCommand { ip_object: parsed_network(args)? }))
+        })
+    }
+}
+
+// Command to get the wildcard (ACL inverse) mask
+struct GetIpWildcardCommand {
+    ip_object: IpNetwork,
+}
+
+impl MyToolsAddonCommand for GetIpWildcardCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let width = ip_width(&self.ip_object.ip());
+        Ok(CommandResult::field("wildcard", int_to_ip(host_mask(width, self.ip_object.prefix()), self.ip_object.is_ipv4()).to_string()))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("get wildcard", "Get the wildcard (ACL inverse) mask for a given IPv4 or IPv6 object", |args| {
+            Ok(Box::new(GetIpWildcardCommand { ip_object: parsed_network(args)? }))
+        })
+    }
+}
+
+// Command to convert an IP address to its other representations
+struct ConvertCommand {
+    ip: IpAddr,
+}
+
+impl MyToolsAddonCommand for ConvertCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let value = ip_to_int(&self.ip);
+        Ok(labelled_result(vec![
+            ("address", if self.ip.is_ipv4() { "Dotted" } else { "Address" }, self.ip.to_string().into()),
+            ("integer", "Integer", value.into()),
+            ("hex", "Hex", format!("{:#x}", value).into()),
+            ("octal", "Octal", format!("0{:o}", value).into()),
+            ("binary", "Binary", format_binary(&self.ip).into()),
+        ], 9))
     }
 
-    fn get_command_input() -> CommandInputs {
+    fn get_command_specs() -> Vec<CommandSpec> {
         vec![
-            "get network <ip/cidr>".to_string(),
-            "get network <ip/mask>".to_string(),
-            "get network <ip> <cidr>".to_string(),
-            "get network <ip> <mask>".to_string(),
+            CommandSpec::new("convert", "Convert an IP address to its dotted, integer, hex, octal and binary forms", |args| {
+                Ok(Box::new(ConvertCommand { ip: args.parsed_arg("ip|integer|0xhex")? }))
+            })
+            .arg(ArgSpec::required("ip|integer|0xhex", ADDRESS_OR_INTEGER))
         ]
     }
+}
+
+// Command to get the binary form of the IP address, with the network/host boundary marked
+struct GetIpBinaryCommand {
+    ip_object: IpNetwork,
+}
+
+impl MyToolsAddonCommand for GetIpBinaryCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let ip = self.ip_object.ip();
+        let prefix = self.ip_object.prefix();
+        let group = if ip.is_ipv4() { 8 } else { 16 };
+        let mut binary = format_binary(&ip);
+
+        // The boundary replaces the separator when it falls between two groups
+        let index = binary_index(prefix, ip.is_ipv4());
+        if prefix == ip_width(&ip) {
+            binary.push('|');
+        } else if prefix > 0 && prefix.is_multiple_of(group) {
+            binary.replace_range(index - 1..index, "|");
+        } else {
+            binary.insert(index, '|');
+        }
+        Ok(CommandResult::field("binary", binary))
+    }
 
-    fn get_command_help() -> CommandHelp {
-        "Get the IP network address for a given IP object".to_string()
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("get binary", "Get the binary form of the IP address, with '|' between the network and host bits", |args| {
+            Ok(Box::new(GetIpBinaryCommand { ip_object: parsed_network(args)? }))
+        })
     }
 }
-// Addon structure
-pub struct IpNetworkAddon;
 
-impl MyToolsAddon for IpNetworkAddon {
-    fn get_keyword(&self) -> &'static str {
-        "ipnet"
+// Command to get the hex form of the IP address
+struct GetIpHexCommand {
+    ip_object: IpNetwork,
+}
+
+impl MyToolsAddonCommand for GetIpHexCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        Ok(CommandResult::field("hex", format!("{:#x}", ip_to_int(&self.ip_object.ip()))))
     }
 
-    fn parse(&self, args: &[String]) -> Result<Box<dyn MyToolsAddonCommand>, MyToolsError> {
-        // Convert &[String] to &[&str]
-        let args: Vec<&str> = args
-            .iter()
-            .map(|s| s.as_str())
-            .collect();
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("get hex", "Get the hex form of the IP address", |args| {
+            Ok(Box::new(GetIpHexCommand { ip_object: parsed_network(args)? }))
+        })
+    }
+}
+
+// Command to get the integer form of the IP address
+struct GetIpIntCommand {
+    ip_object: IpNetwork,
+}
 
-        // Get the help message if args correspond to "--help" or "-h"
-        self.call_help(&args);
+impl MyToolsAddonCommand for GetIpIntCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        Ok(CommandResult::field("integer", ip_to_int(&self.ip_object.ip())))
+    }
 
-        // Parse the arguments and return the corresponding command
-        match args[..] {
-            // GetIpAddress
-            ["get", "address", arg1] => {
-                let ip_object = arg_to_ipv4network(arg1, None)?;
-                Ok(Box::new(GetIpAddressCommand { ip_object }))
-            },
-            ["get", "address", arg1, arg2] => {
-                let ip_object = arg_to_ipv4network(arg1, Some(arg2))?;
-                Ok(Box::new(GetIpAddressCommand { ip_object }))
-            },
-            // GetIpNetmask
-            ["get", "netmask", arg1] => {
-                let ip_object = arg_to_ipv4network(arg1, None)?;
-                Ok(Box::new(GetIpNetmaskCommand { ip_object }))
-            },
-            ["get", "netmask", arg1, arg2] => {
-                let ip_object = arg_to_ipv4network(arg1, Some(arg2))?;
-                Ok(Box::new(GetIpNetmaskCommand { ip_object }))
-            },
-            // GetIpNetwork
-            ["get", "network", arg1] => {
-                let ip_object = arg_to_ipv4network(arg1, None)?;
-                Ok(Box::new(GetIpNetworkCommand { ip_object }))
-            },
-            ["get", "network", arg1, arg2] => {
-                let ip_object = arg_to_ipv4network(arg1, Some(arg2))?;
-                Ok(Box::new(GetIpNetworkCommand { ip_object }))
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("get int", "Get the integer form of the IP address", |args| {
+            Ok(Box::new(GetIpIntCommand { ip_object: parsed_network(args)? }))
+        })
+    }
+}
 
-            },
-            _ => Err(MyToolsError::InvalidCommand(format!("Invalid command: {}\n", args.join(" "))))
+// Command to get the full breakdown of a subnet
+struct IpInfoCommand {
+    ip_object: IpNetwork,
+}
+
+impl MyToolsAddonCommand for IpInfoCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let ip_object = self.ip_object;
+        let prefix = ip_object.prefix();
+        let width = ip_width(&ip_object.ip());
+        let network = ip_object.network();
+        let host_bits = width - prefix;
+
+        // Total number of addresses in the subnet (None for an IPv6 /0)
+        let total_hosts = 1u128.checked_shl(host_bits as u32);
+        let HostRange { broadcast, first: first_host, last: last_host, usable: usable_hosts } = host_range(&ip_object);
+
+        let format_count = |count: Option<u128>| match count {
+            Some(count) => CommandValue::from(count),
+            None => CommandValue::Number(IPV6_ADDRESS_COUNT.to_string()),
+        };
+        let format_ip = |value: u128| int_to_ip(value, ip_object.is_ipv4()).to_string();
+
+        Ok(labelled_result(vec![
+            ("address", "Address", ip_object.ip().to_string().into()),
+            ("netmask", "Netmask", ip_object.mask().to_string().into()),
+            ("wildcard", "Wildcard mask", format_ip(host_mask(width, prefix)).into()),
+            ("network", "Network", network.to_string().into()),
+            ("broadcast", "Broadcast", broadcast.map(format_ip).into()),
+            ("first_host", "First host", format_ip(first_host).into()),
+            ("last_host", "Last host", format_ip(last_host).into()),
+            ("total_hosts", "Total hosts", format_count(total_hosts)),
+            ("usable_hosts", "Usable hosts", format_count(usable_hosts)),
+            ("prefix_length", "Prefix length", prefix.into()),
+            ("address_class", "Address class", address_class(&network).into()),
+            ("address_types", "Address type", address_types(&ip_object).into()),
+        ], 15))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("info", "Get the full breakdown (netmask, wildcard, broadcast, hosts, class, type) of a subnet", |args| {
+            Ok(Box::new(IpInfoCommand { ip_object: parsed_network(args)? }))
+        })
+    }
+}
+
+// Command to split a network into smaller subnets
+struct SplitNetworkCommand {
+    ip_object: IpNetwork,
+    new_prefix: u8,
+    limit: u128,
+    offset: u128,
+}
+
+impl MyToolsAddonCommand for SplitNetworkCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let prefix = self.ip_object.prefix();
+        let width = ip_width(&self.ip_object.ip());
+        let is_ipv4 = self.ip_object.is_ipv4();
+
+        if self.new_prefix < prefix || self.new_prefix > width {
+            return Err(MyToolsError::ExecutionCommandError(format!("New prefix /{} must be between /{} and /{}", self.new_prefix, prefix, width)))
+        }
+
+        // Number of child subnets (None when it exceeds u128, i.e. ::/0 split into /128)
+        let count = 1u128.checked_shl((self.new_prefix - prefix) as u32);
+        let step = 1u128.checked_shl((width - self.new_prefix) as u32).unwrap_or(0);
+        let network_int = ip_to_int(&self.ip_object.network());
+
+        let mut lines: Vec<String> = Vec::new();
+        let mut index = self.offset;
+        while count.is_none_or(|count| index < count) && index - self.offset < self.limit {
+            let subnet = int_to_ip(network_int + index * step, is_ipv4);
+            lines.push(format!("{}/{}", subnet, self.new_prefix));
+            // The last /128 of ::/0 has no next index
+            index = match index.checked_add(1) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+        let value = records("network", lines.clone());
+
+        // Tell the user when the output has been capped
+        let total = count.map_or(IPV6_ADDRESS_COUNT.to_string(), |count| count.to_string());
+        if count.is_none_or(|count| index < count) || self.offset > 0 {
+            lines.push(format!("... showing {} of {} subnets (use --limit and --offset to page)", lines.len(), total));
         }
+
+        Ok(CommandResult::with_text(value, lines.join("\n")))
     }
 
+    fn get_command_specs() -> Vec<CommandSpec> {
+        let help = format!("Split a network into subnets of a given prefix, or of the smallest prefix fitting N hosts (at most {} subnets are listed by default)", SPLIT_DEFAULT_LIMIT);
+        let build: CommandBuilder = |args| {
+            let ip_object = parsed_network(args)?;
+            let new_prefix = match args.number::<u128>("hosts")? {
+                Some(hosts) => prefix_for_hosts(ip_width(&ip_object.ip()), hosts)
+                    .filter(|new_prefix| *new_prefix >= ip_object.prefix())
+                    .ok_or(MyToolsError::ExecutionCommandError(format!("{} hosts do not fit in {}", hosts, ip_object)))?,
+                None => args.parsed_arg("new-prefix")?,
+            };
+            let limit = args.number::<u128>("limit")?.unwrap_or(SPLIT_DEFAULT_LIMIT);
+            let offset = args.number::<u128>("offset")?.unwrap_or(0);
+            Ok(Box::new(SplitNetworkCommand { ip_object, new_prefix, limit, offset }))
+        };
 
-    fn get_list_commands(&self) -> Vec<CommandInputsHelp> {
-        vec![
-            CommandInputsHelp {
-                inputs_msg: GetIpAddressCommand::get_command_input(),
-                help_msg: GetIpAddressCommand::get_command_help()
-            },
-            CommandInputsHelp {
-                inputs_msg: GetIpNetmaskCommand::get_command_input(),
-                help_msg: GetIpNetmaskCommand::get_command_help()
-            },
-            CommandInputsHelp {
-                inputs_msg: GetIpNetworkCommand::get_command_input(),
-                help_msg: GetIpNetworkCommand::get_command_help()
-            },
-        ]
+        // The new prefix is either given as the last argument or by --hosts
+        let by_prefix = network_specs("split", &help, build)
+            .into_iter()
+            .map(|spec| spec.arg(ArgSpec::required("new-prefix", PREFIX)));
+        let by_hosts = network_specs("split", &help, build)
+            .into_iter()
+            .map(|spec| spec.option(OptionSpec::value("hosts", "N", ValueType::Unsigned, "Number of hosts each subnet must fit").required()));
+        by_prefix
+            .chain(by_hosts)
+            .map(|spec| spec
+                .option(OptionSpec::value("limit", "N", ValueType::Unsigned, "Maximum number of subnets listed"))
+                .option(OptionSpec::value("offset", "N", ValueType::Unsigned, "Number of subnets skipped before the first one listed")))
+            .collect()
     }
 }
 
+// Command to allocate named subnets in a parent network (VLSM)
+struct VlsmCommand {
+    ip_object: IpNetwork,
+    requirements: Vec<(String, u128)>,
+}
+
+impl MyToolsAddonCommand for VlsmCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let width = ip_width(&self.ip_object.ip());
+        let is_ipv4 = self.ip_object.is_ipv4();
+        let parent_start = ip_to_int(&self.ip_object.network());
+        let parent_end = parent_start | host_mask(width, self.ip_object.prefix());
 
-/// Function to parse arguments to a IPv4Network object
-fn arg_to_ipv4network(arg1: &str, arg2: Option<&str>) -> Result<Ipv4Network, MyToolsError> {
-    match arg2 {
-        // Handling 2 arguments
-        Some(cidr_netmask) => {
-            // Try to parse arg1 as an Ipv4Addr, if not, return ParseCommandError
-            return parse_args(arg1, cidr_netmask)
-        },
-        // Handling 1 argument
-        None => {
-            // Check if arg1 contains the '/' separator
-            if !arg1.contains("/") {
-                return Err(MyToolsError::ParseCommandError("Separator '/' is missing".to_string()))
+        // Allocate the largest requirements first, so that every block stays aligned
+        let mut requirements: Vec<&(String, u128)> = self.requirements.iter().collect();
+        requirements.sort_by_key(|requirement| std::cmp::Reverse(requirement.1));
+
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        let mut allocations: Vec<CommandValue> = Vec::new();
+        let mut next: Option<u128> = Some(parent_start);
+        for (name, hosts) in requirements {
+            let prefix = prefix_for_hosts(width, *hosts)
+                .filter(|prefix| *prefix >= self.ip_object.prefix());
+            let (start, prefix) = match (next, prefix) {
+                (Some(start), Some(prefix)) if start | host_mask(width, prefix) <= parent_end => (start, prefix),
+                _ => return Err(MyToolsError::ExecutionCommandError(format!("Requirements do not fit in {}: no room left for '{}' ({} hosts)", self.ip_object, name, hosts))),
             };
 
-            // Split the argument into ip and cidr/netmask
-            let (ip, cidr_netmask) = arg1.split_once("/").unwrap();
+            let subnet = IpNetwork::new(int_to_ip(start, is_ipv4), prefix).unwrap();
+            let range = host_range(&subnet);
+            let wasted = range.usable.map_or(IPV6_ADDRESS_COUNT.to_string(), |usable| (usable - hosts).to_string());
+            rows.push(vec![
+                name.to_string(),
+                subnet.to_string(),
+                format!("{} - {}", int_to_ip(range.first, is_ipv4), int_to_ip(range.last, is_ipv4)),
+                hosts.to_string(),
+                wasted.to_string(),
+            ]);
+            allocations.push(CommandValue::object([
+                ("name", name.as_str().into()),
+                ("subnet", subnet.to_string().into()),
+                ("first_host", int_to_ip(range.first, is_ipv4).to_string().into()),
+                ("last_host", int_to_ip(range.last, is_ipv4).to_string().into()),
+                ("hosts", (*hosts).into()),
+                ("wasted", CommandValue::Number(wasted)),
+            ]));
 
-            return parse_args(ip, cidr_netmask)
+            // None once the end of the address space has been reached
+            next = (start | host_mask(width, prefix)).checked_add(1);
         }
+
+        Ok(CommandResult::with_text(allocations.into(), format_table(&["Name", "Subnet", "Usable range", "Hosts", "Wasted"], &rows)))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("vlsm", "Allocate named subnets of the given host counts in a parent network, largest first", |args| {
+            let ip_object = parsed_network(args)?;
+            let requirements = args.parsed_values("name=hosts");
+            Ok(Box::new(VlsmCommand { ip_object, requirements }))
+        })
+            .into_iter()
+            .map(|spec| spec.arg(ArgSpec::one_or_more("name=hosts", REQUIREMENT)))
+            .collect()
     }
+}
+
+// Command to summarize networks into the minimal covering set of prefixes
+struct SummarizeCommand {
+    ip_objects: Vec<IpNetwork>,
+    supernet: bool,
+}
 
-    // DRY
-    fn parse_args(arg1: &str, arg2: &str) -> Result<Ipv4Network, MyToolsError> {
-        if let Ok(ip) = arg1.parse::<Ipv4Addr>() {
-            // Check if cidr_netmask is not empty
-            if arg2.is_empty() {
-                return Err(MyToolsError::ParseCommandError("Argument is missing a CIDR or a netmask".to_string()))
+impl MyToolsAddonCommand for SummarizeCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let (ipv4, ipv6): (Vec<IpNetwork>, Vec<IpNetwork>) = self.ip_objects
+            .iter()
+            .partition(|ip_object| ip_object.is_ipv4());
+
+        let summary: Vec<IpNetwork> = if self.supernet {
+            if !ipv4.is_empty() && !ipv6.is_empty() {
+                return Err(MyToolsError::ExecutionCommandError("Cannot compute a supernet of both IPv4 and IPv6 networks".to_string()))
             }
+            let ip_objects = if ipv4.is_empty() { ipv6 } else { ipv4 };
+            let is_ipv4 = ip_objects[0].is_ipv4();
+            let (start, end) = ip_objects
+                .iter()
+                .map(network_bounds)
+                .reduce(|(start1, end1), (start2, end2)| (start1.min(start2), end1.max(end2)))
+                .unwrap();
+            vec![supernet_of(start, end, is_ipv4)]
+        } else {
+            [(ipv4, true), (ipv6, false)]
+                .into_iter()
+                .flat_map(|(ip_objects, is_ipv4)| {
+                    ranges_to_cidrs(&merge_ranges(ip_objects.iter().map(network_bounds).collect()), is_ipv4)
+                })
+                .collect()
+        };
 
-            // Try to parse arg2 as a cidr
-            if let Ok(cidr) = arg2.parse::<u8>() {
-                if cidr <= 32 {
-                    return Ok(Ipv4Network::new(ip, cidr).unwrap())
-                }
-            } else {
-                // Try to parse arg2 as a netmask
-                if let Ok(netmask) = arg2.parse::<Ipv4Addr>() {
-                    if is_netmask(&netmask) {
-                        return Ok(Ipv4Network::with_netmask(ip, netmask).unwrap())
-                    }
+        Ok(networks_result(&summary))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("summarize", "Merge adjacent and overlapping networks into the minimal set of prefixes, or into a single supernet ('-' reads the networks from stdin)", |args| {
+                let ip_objects = list_networks(args, "ip/cidr")?;
+                if ip_objects.is_empty() {
+                    return Err(MyToolsError::InvalidCommand("Missing the networks to summarize\n".to_string()))
                 }
-            }
+                Ok(Box::new(SummarizeCommand { ip_objects, supernet: args.flag("supernet") }))
+            })
+            .arg(ArgSpec::zero_or_more("ip/cidr", NETWORK_LIST_ITEM).stdin())
+            .option(OptionSpec::flag("supernet", "Merge into the single smallest network covering them all"))
+            .option(list_input_option())
+        ]
+    }
+}
+
+// Command to convert an inclusive range of addresses to the minimal list of prefixes
+struct RangeToCidrsCommand {
+    start: IpAddr,
+    end: IpAddr,
+}
+
+impl MyToolsAddonCommand for RangeToCidrsCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        if self.start.is_ipv4() != self.end.is_ipv4() {
+            return Err(MyToolsError::ExecutionCommandError(format!("Range bounds '{}' and '{}' are not of the same family", self.start, self.end)))
+        }
+        if self.start > self.end {
+            return Err(MyToolsError::ExecutionCommandError(format!("Range start '{}' is greater than range end '{}'", self.start, self.end)))
+        }
+
+        Ok(networks_result(&range_to_cidrs(ip_to_int(&self.start), ip_to_int(&self.end), self.start.is_ipv4())))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        let help = "Convert an inclusive range of addresses to the minimal list of prefixes";
+        let build: CommandBuilder = |args| {
+            let (start, end) = match args.parsed("start-end") {
+                Some(range) => range,
+                None => (args.parsed_arg("start-ip")?, args.parsed_arg("end-ip")?),
+            };
+            Ok(Box::new(RangeToCidrsCommand { start, end }))
+        };
+        vec![
+            CommandSpec::new("range", help, build)
+                .arg(ArgSpec::required("start-end", RANGE)),
+            CommandSpec::new("range", help, build)
+                .arg(ArgSpec::required("start-ip", ADDRESS))
+                .arg(ArgSpec::required("end-ip", ADDRESS)),
+        ]
+    }
+}
+
+// Command to convert a network to its range of addresses
+struct CidrToRangeCommand {
+    ip_object: IpNetwork,
+}
 
-            // Return Err in case arg2 is neither a valid CIDR nor a valid netmask
-            Err(MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg2)))
+impl MyToolsAddonCommand for CidrToRangeCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let (start, end) = network_bounds(&self.ip_object);
+        let is_ipv4 = self.ip_object.is_ipv4();
+        let (first, last) = (int_to_ip(start, is_ipv4), int_to_ip(end, is_ipv4));
+        Ok(CommandResult::with_text(
+            CommandValue::object([("first", first.to_string().into()), ("last", last.to_string().into())]),
+            format!("{} - {}", first, last)
+        ))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        let help = "Convert a network to its first and last address (a second argument shaped like a netmask is read as one, write '<start-ip>-<end-ip>' for such a range)";
+        let build: CommandBuilder = |args| {
+            Ok(Box::new(CidrToRangeCommand { ip_object: parsed_network(args)? }))
+        };
+        // A second argument which is neither a prefix length nor a contiguous netmask is the end of a range
+        vec![
+            CommandSpec::new("range", help, build)
+                .arg(ArgSpec::required("ip/cidr", RANGE_NETWORK)),
+            CommandSpec::new("range", help, build)
+                .arg(ArgSpec::required("ip", ADDRESS))
+                .arg(ArgSpec::required("cidr|mask", PREFIX_OR_NETMASK)),
+        ]
+    }
+}
+
+// Command to check if a network contains an address or another network
+struct ContainsCommand {
+    ip_object: IpNetwork,
+    other: IpNetwork,
+}
+
+impl MyToolsAddonCommand for ContainsCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let (start, end) = network_bounds(&self.ip_object);
+        let (other_start, other_end) = network_bounds(&self.other);
+
+        if self.ip_object.is_ipv4() == self.other.is_ipv4() && start <= other_start && other_end <= end {
+            Ok(CommandResult::field("contains", true))
         } else {
-            Err(MyToolsError::ParseCommandError(format!("Invalid IP address: '{}'", arg1)))
+            Err(MyToolsError::NegativeResult(CommandResult::field("contains", false)))
         }
     }
 
-    fn is_netmask(addr: &Ipv4Addr) -> bool {
-        let addr_bit = addr.octets()
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("contains", "Check if a network contains an address or a network (exit status 1 if not)", |args| {
+                let ip_object = args.parsed_arg("ip/cidr")?;
+                let other = args.parsed_arg("ip|ip/cidr")?;
+                Ok(Box::new(ContainsCommand { ip_object, other }))
+            })
+            .arg(ArgSpec::required("ip/cidr", NETWORK))
+            .arg(ArgSpec::required("ip|ip/cidr", NETWORK_OR_HOST))
+        ]
+    }
+}
+
+// Command to find the overlapping networks in a list
+struct OverlapCommand {
+    ip_objects: Vec<IpNetwork>,
+}
+
+impl MyToolsAddonCommand for OverlapCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        // Sort by first address, so only the following networks starting before the end can overlap
+        let mut ip_objects: Vec<(bool, u128, u128, &IpNetwork)> = self.ip_objects
             .iter()
-            .fold(0u32, |acc, &octet| acc << 8 | octet as u32);
-
-        let mut found_zero = false;
-        for i in 0..32 {
-            if addr_bit & (1 << (31 - i)) == 0 {
-                found_zero = true;
-            } else if found_zero {
-                return false;
+            .map(|ip_object| {
+                let (start, end) = network_bounds(ip_object);
+                (ip_object.is_ipv4(), start, end, ip_object)
+            })
+            .collect();
+        ip_objects.sort_by_key(|(is_ipv4, start, end, _)| (*is_ipv4, *start, *end));
+
+        let mut overlaps: Vec<String> = Vec::new();
+        let mut pairs: Vec<CommandValue> = Vec::new();
+        for (i, (is_ipv4, _, end, ip_object)) in ip_objects.iter().enumerate() {
+            for (other_is_ipv4, other_start, other_end, other) in &ip_objects[i + 1..] {
+                if other_is_ipv4 != is_ipv4 || other_start > end {
+                    break;
+                }
+                let (first, last) = (int_to_ip(*other_start, *is_ipv4), int_to_ip(*end.min(other_end), *is_ipv4));
+                overlaps.push(format!("{} and {} overlap: {} - {}", ip_object, other, first, last));
+                pairs.push(CommandValue::object([
+                    ("network", ip_object.to_string().into()),
+                    ("other", other.to_string().into()),
+                    ("first", first.to_string().into()),
+                    ("last", last.to_string().into()),
+                ]));
             }
         }
-        true
+
+        if overlaps.is_empty() {
+            Err(MyToolsError::NegativeResult(CommandResult::with_text(CommandValue::List(Vec::new()), "no")))
+        } else {
+            Ok(CommandResult::with_text(pairs.into(), format!("yes\n{}", overlaps.join("\n"))))
+        }
     }
-}
 
-// Tests for arg_to_ipv4network
-// Tests with 1 argument
-#[test]
-fn arg_to_ipv4network_test_1_arg_ip_nok() {
-    let arg1 = "this is not an ip/8";
-    let arg_object = arg_to_ipv4network(arg1, None);
-    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Invalid IP address: '{}'", arg1.split_once("/").unwrap().0)))
-}
-#[test]
-fn arg_to_ipv4network_test_1_arg_no_separator() {
-    let arg1 = "127.0.0.1-8";
-    let arg_object = arg_to_ipv4network(arg1, None);
-    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError("Separator '/' is missing".to_string()))
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("overlap", "Check if networks overlap and list every overlapping pair, exit status 1 if none ('-' reads the networks from stdin)", |args| {
+                let ip_objects = list_networks(args, "ip/cidr")?;
+                if ip_objects.len() < 2 && !args.values("ip/cidr").contains(&"-") && args.value("input").is_none() {
+                    return Err(MyToolsError::InvalidCommand("At least 2 networks are needed to check overlaps\n".to_string()))
+                }
+                Ok(Box::new(OverlapCommand { ip_objects }))
+            })
+            .arg(ArgSpec::zero_or_more("ip/cidr", NETWORK_LIST_ITEM).stdin())
+            .option(list_input_option())
+        ]
+    }
 }
 
-#[test]
-fn arg_to_ipv4network_test_1_arg_ip_ok_cidr_or_netmask_missing_1() {
-    let arg1 = "127.0.0.1";
-    let arg_object = arg_to_ipv4network(arg1, None);
-    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError("Separator '/' is missing".to_string()))
+// Command to remove networks from a base network
+struct ExcludeCommand {
+    ip_object: IpNetwork,
+    excluded: Vec<IpNetwork>,
 }
 
-#[test]
-fn arg_to_ipv4network_test_1_arg_ip_ok_cidr_or_netmask_missing_2() {
-    let arg1 = "127.0.0.1/";
-    let arg_object = arg_to_ipv4network(arg1, None);
-    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError("Argument is missing a CIDR or a netmask".to_string()))
-}
+impl MyToolsAddonCommand for ExcludeCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let is_ipv4 = self.ip_object.is_ipv4();
+        let excluded = self.excluded.iter().map(network_bounds).collect();
 
-#[test]
-fn arg_to_ipv4network_test_1_arg_ip_ok_cidr_ok() {
-    let arg1 = "127.0.0.1/24";
-    let arg_object = arg_to_ipv4network(arg1, None);
-    assert_eq!(arg_object.unwrap(), Ipv4Network::new(Ipv4Addr::new(127, 0, 0, 1), 24).unwrap())
+        let ranges = subtract_ranges(vec![network_bounds(&self.ip_object)], merge_ranges(excluded));
+        Ok(networks_result(&ranges_to_cidrs(&ranges, is_ipv4)))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("exclude", "Remove networks from a base network and list the remaining prefixes", |args| {
+                let ip_object = args.parsed_arg("ip/cidr")?;
+                let excluded = list_networks(args, "excluded")?;
+                check_same_family(&ip_object, &excluded)?;
+                Ok(Box::new(ExcludeCommand { ip_object, excluded }))
+            })
+            .arg(ArgSpec::required("ip/cidr", NETWORK))
+            .arg(ArgSpec::zero_or_more("excluded", NETWORK_LIST_ITEM).stdin())
+            .option(list_input_option())
+        ]
+    }
 }
 
-#[test]
-fn arg_to_ipv4network_test_1_arg_ip_ok_cidr_nok() {
-    let arg1 = "127.0.0.1/33";
-    let arg_object = arg_to_ipv4network(arg1, None);
-    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg1.split_once("/").unwrap().1)))
+// Command to get the addresses shared by every network
+struct IntersectCommand {
+    ip_objects: Vec<IpNetwork>,
 }
 
-#[test]
-fn arg_to_ipv4network_test_1_arg_ip_ok_netmask_ok() {
-    let arg1 = "127.0.0.1/255.0.0.0";
-    let arg_object = arg_to_ipv4network(arg1, None);
-    assert_eq!(arg_object.unwrap(), Ipv4Network::with_netmask(Ipv4Addr::new(127, 0, 0, 1), Ipv4Addr::new(255, 0, 0, 0)).unwrap())
+impl MyToolsAddonCommand for IntersectCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        // No networks (e.g. an empty stdin), nothing shared
+        let Some(first) = self.ip_objects.first() else {
+            return Ok(networks_result(&[]))
+        };
+        // Networks of different families never intersect
+        let is_ipv4 = first.is_ipv4();
+        if self.ip_objects.iter().any(|ip_object| ip_object.is_ipv4() != is_ipv4) {
+            return Ok(networks_result(&[]))
+        }
+
+        let ranges = self.ip_objects
+            .iter()
+            .map(|ip_object| vec![network_bounds(ip_object)])
+            .reduce(|acc, ranges| intersect_ranges(&acc, &ranges))
+            .unwrap();
+        Ok(networks_result(&ranges_to_cidrs(&ranges, is_ipv4)))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("intersect", "List the prefixes shared by every network (empty if none)", |args| {
+                Ok(Box::new(IntersectCommand { ip_objects: required_list_networks(args, "intersect")? }))
+            })
+            .arg(ArgSpec::zero_or_more("ip/cidr", NETWORK_LIST_ITEM).stdin())
+            .option(list_input_option())
+        ]
+    }
 }
 
-#[test]
-fn arg_to_ipv4network_test_1_arg_ip_ok_netmask_nok() {
-    let arg1 = "127.0.0.1/255.255.255.256";
-    let arg_object = arg_to_ipv4network(arg1, None);
-    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg1.split_once("/").unwrap().1)))
+// Command to get the addresses of any of the networks
+struct UnionCommand {
+    ip_objects: Vec<IpNetwork>,
 }
 
-// Tests with 2 arguments
-#[test]
-fn arg_to_ipv4network_test_2_args_ip_nok_1() {
-    let arg1 = "this is not an ip";
-    let arg2 = "8";
-    let arg_object = arg_to_ipv4network(arg1, Some(arg2));
-    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Invalid IP address: '{}'", arg1)))
+impl MyToolsAddonCommand for UnionCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let networks: Vec<IpNetwork> = [true, false]
+            .into_iter()
+            .flat_map(|is_ipv4| {
+                let ranges = self.ip_objects
+                    .iter()
+                    .filter(|ip_object| ip_object.is_ipv4() == is_ipv4)
+                    .map(network_bounds)
+                    .collect();
+                ranges_to_cidrs(&merge_ranges(ranges), is_ipv4)
+            })
+            .collect();
+        Ok(networks_result(&networks))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("union", "List the minimal prefixes covering every network", |args| {
+                Ok(Box::new(UnionCommand { ip_objects: required_list_networks(args, "unite")? }))
+            })
+            .arg(ArgSpec::zero_or_more("ip/cidr", NETWORK_LIST_ITEM).stdin())
+            .option(list_input_option())
+        ]
+    }
 }
 
-#[test]
-fn arg_to_ipv4network_test_2_args_ip_nok_2() {
-    let arg1 = "127.0.0.256";
-    let arg2 = "8";
-    let arg_object = arg_to_ipv4network(arg1, Some(arg2));
-    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Invalid IP address: '{}'", arg1)))
+// Command to enumerate the hosts of a network
+struct HostsCommand {
+    ip_object: IpNetwork,
+    include_network_broadcast: bool,
+    limit: Option<u128>,
+    offset: u128,
+    step: u128,
 }
 
-#[test]
-fn arg_to_ipv4network_test_2_arg_ip_ok_cidr_or_netmask_missing() {
-    let arg1 = "127.0.0.1";
-    let arg2 = "";
-    let arg_object = arg_to_ipv4network(arg1, Some(arg2));
-    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError("Argument is missing a CIDR or a netmask".to_string()))
+impl HostsCommand {
+    /// Function to iterate over the listed hosts
+    fn hosts(&self) -> impl Iterator<Item = IpAddr> + '_ {
+        let is_ipv4 = self.ip_object.is_ipv4();
+        let (first, last) = host_bounds(&self.ip_object, self.include_network_broadcast);
+        let limit = self.limit.map_or(usize::MAX, |limit| limit.min(usize::MAX as u128) as usize);
+
+        std::iter::successors(first.checked_add(self.offset), |host| host.checked_add(self.step))
+            .take_while(move |host| *host <= last)
+            .take(limit)
+            .map(move |host| int_to_ip(host, is_ipv4))
+    }
 }
 
-#[test]
-fn arg_to_ipv4network_test_2_args_ip_ok_cidr_ok() {
-    let arg_object = arg_to_ipv4network("127.0.0.1", Some("8")).unwrap();
-    let ip_object = Ipv4Network::new(Ipv4Addr::new(127, 0, 0, 1), 8).unwrap();
-    assert_eq!(arg_object, ip_object)
+impl MyToolsAddonCommand for HostsCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        Ok(CommandResult::new(records("address", self.hosts().map(|host| host.to_string()))))
+    }
+
+    fn execute_to(&self, output: &mut ResultWriter) -> Result<(), MyToolsError> {
+        // Hosts are streamed one by one, so even a /8 does not need to fit in memory
+        output.begin_list()?;
+        for host in self.hosts() {
+            if !output.write_item(&CommandResult::field("address", host.to_string()))? {
+                break;
+            }
+        }
+        output.end_list()?;
+        Ok(())
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("hosts", "List the usable hosts of a network", |args| {
+            let step = args.number::<u128>("step")?.unwrap_or(1);
+            if step == 0 {
+                return Err(MyToolsError::ParseCommandError("Invalid step: '0'".to_string()))
+            }
+            Ok(Box::new(HostsCommand {
+                ip_object: parsed_network(args)?,
+                include_network_broadcast: args.flag("include-network-broadcast"),
+                limit: args.number::<u128>("limit")?,
+                offset: args.number::<u128>("offset")?.unwrap_or(0),
+                step,
+            }))
+        })
+            .into_iter()
+            .map(|spec| spec
+                .option(OptionSpec::flag("include-network-broadcast", "Also list the network and broadcast addresses"))
+                .option(OptionSpec::value("limit", "N", ValueType::Unsigned, "Maximum number of hosts listed"))
+                .option(OptionSpec::value("offset", "N", ValueType::Unsigned, "Number of hosts skipped before the first one listed"))
+                .option(OptionSpec::value("step", "N", ValueType::Unsigned, "List every Nth host")))
+            .collect()
+    }
 }
 
-#[test]
-fn arg_to_ipv4network_test_2_args_ip_ok_cidr_nok() {
-    let arg2 = "33";
-    let arg_object = arg_to_ipv4network("127.0.0.1", Some(arg2));
-    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg2)))
+// Command to get the N-th host of a network
+struct NthHostCommand {
+    ip_object: IpNetwork,
+    include_network_broadcast: bool,
+    index: i128,
 }
 
-#[test]
-fn arg_to_ipv4network_test_2_args_ip_ok_netmask_ok() {
-    let arg_object = arg_to_ipv4network("127.0.0.1", Some("255.255.255.0")).unwrap();
-    let ip_object = Ipv4Network::with_netmask(Ipv4Addr::new(127, 0, 0, 1), Ipv4Addr::new(255, 255, 255, 0)).unwrap();
-    assert_eq!(arg_object, ip_object)
+impl MyToolsAddonCommand for NthHostCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let (first, last) = host_bounds(&self.ip_object, self.include_network_broadcast);
+
+        // Negative indexes count from the end, -1 being the last host
+        let host = if self.index >= 0 {
+            first.checked_add(self.index as u128).filter(|host| *host <= last)
+        } else {
+            last.checked_sub(self.index.unsigned_abs() - 1).filter(|host| *host >= first)
+        };
+
+        match host {
+            Some(host) => Ok(CommandResult::field("address", int_to_ip(host, self.ip_object.is_ipv4()).to_string())),
+            None => Err(MyToolsError::ExecutionCommandError(format!("Index {} is out of the hosts of {}", self.index, self.ip_object))),
+        }
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("nth", "Get the host at the given index of a network, starting at 0 (negative indexes count from the end)", |args| {
+            Ok(Box::new(NthHostCommand {
+                ip_object: parsed_network(args)?,
+                include_network_broadcast: args.flag("include-network-broadcast"),
+                index: args.number::<i128>("index")?.unwrap_or_default(),
+            }))
+        })
+            .into_iter()
+            .map(|spec| spec
+                .arg(ArgSpec::required("index", ValueType::Integer))
+                .option(OptionSpec::flag("include-network-broadcast", "Count the network and broadcast addresses as hosts")))
+            .collect()
+    }
 }
 
-#[test]
-fn arg_to_ipv4network_test_2_args_ip_ok_netmask_nok_1() {
-    let arg2 = "255.255.255.256";
-    let arg_object = arg_to_ipv4network("127.0.0.1", Some(arg2));
-    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg2)))
+// Command to explain why a mask is (or is not) a valid netmask
+struct DiagnoseMaskCommand {
+    mask: IpAddr,
 }
 
-#[test]
-fn arg_to_ipv4network_test_2_args_ip_ok_netmask_nok_2() {
-    let arg2 = "255.255.0.128";
-    let arg_object = arg_to_ipv4network("127.0.0.1", Some(arg2));
-    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg2)))
+impl MyToolsAddonCommand for DiagnoseMaskCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let width = ip_width(&self.mask);
+        let is_ipv4 = self.mask.is_ipv4();
+        let value = ip_to_int(&self.mask);
+
+        // A netmask is valid when every 1 bit is before the first 0 bit
+        let leading_ones = ((value << (128 - width as u32)).leading_ones() as u8).min(width);
+        let last_one = if value == 0 { 0 } else { width - value.trailing_zeros() as u8 };
+        let netmask = |prefix: u8| int_to_ip(!host_mask(width, prefix) & host_mask(width, 0), is_ipv4);
+        let nearest = |prefix: u8| CommandValue::object([
+            ("netmask", netmask(prefix).to_string().into()),
+            ("prefix_length", prefix.into()),
+        ]);
+        let valid = leading_ones == last_one;
+
+        let mut lines: Vec<(&str, String)> = vec![
+            ("Mask", self.mask.to_string()),
+            ("Binary", format_binary(&self.mask)),
+        ];
+        if valid {
+            lines.push(("Valid", format!("yes, /{}", leading_ones)));
+        } else {
+            lines.push(("", format!("{}^", " ".repeat(binary_index(leading_ones, is_ipv4)))));
+            lines.push(("Valid", format!("no, bit {} is 0 but is followed by 1 bits", leading_ones + 1)));
+            lines.push(("Nearest below", format!("{} (/{})", netmask(leading_ones), leading_ones)));
+            lines.push(("Nearest above", format!("{} (/{})", netmask(last_one), last_one)));
+        }
+
+        let value = CommandValue::object([
+            ("mask", self.mask.to_string().into()),
+            ("binary", format_binary(&self.mask).into()),
+            ("valid", valid.into()),
+            ("prefix_length", valid.then_some(leading_ones).into()),
+            ("invalid_bit", (!valid).then_some(leading_ones + 1).into()),
+            ("nearest_below", if valid { CommandValue::Null } else { nearest(leading_ones) }),
+            ("nearest_above", if valid { CommandValue::Null } else { nearest(last_one) }),
+        ]);
+        Ok(CommandResult::with_text(value, lines
+            .iter()
+            .map(|(label, value)| match label {
+                &"" => format!("{:<15}{}", "", value),
+                _ => format!("{:<15}{}", format!("{}:", label), value),
+            })
+            .collect::<Vec<String>>()
+            .join("\n")))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("diagnose", "Explain why a mask is not a valid netmask, with its binary layout and the nearest valid netmasks", |args| {
+                Ok(Box::new(DiagnoseMaskCommand { mask: args.parsed_arg("mask")? }))
+            })
+            .arg(ArgSpec::required("mask", ADDRESS))
+        ]
+    }
+}
+
+// Command to get the reverse DNS (PTR) name of an IP address
+struct PtrCommand {
+    ip: IpAddr,
+}
+
+impl MyToolsAddonCommand for PtrCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        Ok(CommandResult::field("name", reverse_name(&self.ip, ip_width(&self.ip))))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("ptr", "Get the in-addr.arpa or ip6.arpa name of an IP address", |args| {
+                Ok(Box::new(PtrCommand { ip: args.parsed_arg("ip")? }))
+            })
+            .arg(ArgSpec::required("ip", ADDRESS))
+        ]
+    }
+}
+
+// Command to list the reverse DNS zones covering a network
+struct ReverseZonesCommand {
+    ip_object: IpNetwork,
+}
+
+impl MyToolsAddonCommand for ReverseZonesCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let prefix = self.ip_object.prefix();
+        let is_ipv4 = self.ip_object.is_ipv4();
+        let network = self.ip_object.network();
+
+        // Prefixes longer than /24 are delegated with RFC 2317 classless names
+        if is_ipv4 && prefix > 24 {
+            let last_octet = ip_to_int(&network) as u8;
+            return Ok(CommandResult::new(records("zone", [format!("{}/{}.{}", last_octet, prefix, reverse_name(&network, 24))])))
+        }
+
+        // Zones are on octet boundaries for IPv4, and on nibble boundaries for IPv6
+        let group = if is_ipv4 { 8 } else { 4 };
+        let zone_prefix = prefix.div_ceil(group) * group;
+        let width = ip_width(&network);
+        let start = ip_to_int(&network);
+        let step = 1u128.checked_shl((width - zone_prefix) as u32).unwrap_or(0);
+
+        Ok(CommandResult::new(records("zone", (0..1u128 << (zone_prefix - prefix))
+            .map(|index| reverse_name(&int_to_ip(start + index * step, is_ipv4), zone_prefix)))))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("rdns-zones", "List the reverse DNS zones covering a network (RFC 2317 classless names for IPv4 prefixes longer than /24)", |args| {
+            Ok(Box::new(ReverseZonesCommand { ip_object: parsed_network(args)? }))
+        })
+    }
+}
+
+// Command to find the special-purpose registry entries of an address or a network
+struct ClassifyCommand {
+    ip_object: IpNetwork,
+}
+
+impl MyToolsAddonCommand for ClassifyCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let (start, end) = network_bounds(&self.ip_object);
+
+        // Prefixes are either nested or disjoint, so an entry matching only part of the input is inside it
+        let rows: Vec<Vec<String>> = SPECIAL_PURPOSE_REGISTRY
+            .iter()
+            .filter_map(|(prefix, name, rfc)| {
+                let entry = prefix.parse::<IpNetwork>().unwrap();
+                let (entry_start, entry_end) = network_bounds(&entry);
+                if entry.is_ipv4() != self.ip_object.is_ipv4() || entry_end < start || entry_start > end {
+                    return None
+                }
+                let coverage = if entry_start <= start && end <= entry_end { "full" } else { "partial" };
+                Some(vec![prefix.to_string(), name.to_string(), rfc.to_string(), coverage.to_string()])
+            })
+            .collect();
+
+        let entries: Vec<CommandValue> = rows
+            .iter()
+            .map(|row| CommandValue::object(["prefix", "name", "rfc", "coverage"]
+                .into_iter()
+                .zip(row.iter().map(|cell| cell.as_str().into()))))
+            .collect();
+        if rows.is_empty() {
+            return Ok(CommandResult::with_text(entries.into(), format!("{} is not in any special-purpose registry entry", self.ip_object)))
+        }
+        Ok(CommandResult::with_text(entries.into(), format_table(&["Prefix", "Name", "RFC", "Coverage"], &rows)))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        let help = "List the IANA special-purpose registry entries (RFC 6890) of an address or a network";
+        let build: CommandBuilder = |args| {
+            let ip_object = match args.parsed("ip|ip/cidr") {
+                Some(ip_object) => ip_object,
+                None => parsed_network(args)?,
+            };
+            Ok(Box::new(ClassifyCommand { ip_object }))
+        };
+        let mut specs = vec![
+            CommandSpec::new("classify", help, build)
+                .arg(ArgSpec::required("ip|ip/cidr", NETWORK_OR_HOST)),
+        ];
+        specs.extend(network_specs("classify", help, build).into_iter().skip(1));
+        specs
+    }
+}
+
+// Command to add or subtract an offset to an IP address
+struct OffsetIpCommand {
+    ip: IpAddr,
+    offset: u128,
+    subtract: bool,
+}
+
+impl MyToolsAddonCommand for OffsetIpCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let value = ip_to_int(&self.ip);
+        let max = host_mask(ip_width(&self.ip), 0);
+
+        // Going past the first or last address is an error instead of wrapping around
+        let result = if self.subtract {
+            value.checked_sub(self.offset)
+        } else {
+            value.checked_add(self.offset).filter(|result| *result <= max)
+        };
+
+        match result {
+            Some(result) => Ok(CommandResult::field("address", int_to_ip(result, self.ip.is_ipv4()).to_string())),
+            None => Err(MyToolsError::ExecutionCommandError(format!(
+                "{} {} {} is out of the {} address space",
+                self.ip,
+                if self.subtract { "-" } else { "+" },
+                self.offset,
+                if self.ip.is_ipv4() { "IPv4" } else { "IPv6" }
+            ))),
+        }
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        fn build(args: &ParsedArgs, subtract: bool) -> Result<Box<dyn MyToolsAddonCommand>, MyToolsError> {
+            let ip = args.parsed_arg("ip")?;
+            let offset = args.number::<u128>("offset")?.unwrap_or_default();
+            Ok(Box::new(OffsetIpCommand { ip, offset, subtract }))
+        }
+
+        let help = "Add or subtract N to an IP address";
+        vec![
+            CommandSpec::new("add", help, |args| build(args, false)),
+            CommandSpec::new("sub", help, |args| build(args, true)),
+        ]
+            .into_iter()
+            .map(|spec| spec
+                .arg(ArgSpec::required("ip", ADDRESS))
+                .arg(ArgSpec::required("offset", ValueType::Unsigned)))
+            .collect()
+    }
+}
+
+// Command to get the distance between two IP addresses
+struct DiffIpCommand {
+    ip1: IpAddr,
+    ip2: IpAddr,
+}
+
+impl MyToolsAddonCommand for DiffIpCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        if self.ip1.is_ipv4() != self.ip2.is_ipv4() {
+            return Err(MyToolsError::ExecutionCommandError(format!("'{}' and '{}' are not of the same family", self.ip1, self.ip2)))
+        }
+
+        let (value1, value2) = (ip_to_int(&self.ip1), ip_to_int(&self.ip2));
+        if value2 >= value1 {
+            Ok(CommandResult::field("difference", value2 - value1))
+        } else {
+            Ok(CommandResult::field("difference", CommandValue::Number(format!("-{}", value1 - value2))))
+        }
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("diff", "Get the number of addresses from ip1 to ip2 (negative if ip2 is before ip1)", |args| {
+                Ok(Box::new(DiffIpCommand { ip1: args.parsed_arg("ip1")?, ip2: args.parsed_arg("ip2")? }))
+            })
+            .arg(ArgSpec::required("ip1", ADDRESS))
+            .arg(ArgSpec::required("ip2", ADDRESS))
+        ]
+    }
+}
+
+// Command to walk to the next or previous blocks of the same size
+struct NeighbourNetworkCommand {
+    ip_object: IpNetwork,
+    count: u128,
+    previous: bool,
+}
+
+impl NeighbourNetworkCommand {
+    /// Function to iterate over the listed blocks, after checking that there are enough of them
+    fn neighbours(&self) -> Result<impl Iterator<Item = IpNetwork> + '_, MyToolsError> {
+        let prefix = self.ip_object.prefix();
+        let width = ip_width(&self.ip_object.ip());
+        let is_ipv4 = self.ip_object.is_ipv4();
+        let (start, end) = network_bounds(&self.ip_object);
+        // Addresses in a block (None for ::/0, which has no neighbours)
+        let block = host_mask(width, prefix).checked_add(1);
+
+        // Blocks past the first or last address are an error instead of wrapping around
+        let available = block.map_or(0, |block| match self.previous {
+            true => start / block,
+            false => (host_mask(width, 0) - end) / block,
+        });
+        let nth = move |index: u128| {
+            let offset = index * block.unwrap_or_default();
+            let start = if self.previous { start - offset } else { start + offset };
+            IpNetwork::new(int_to_ip(start, is_ipv4), prefix).unwrap()
+        };
+        if self.count > available {
+            return Err(MyToolsError::ExecutionCommandError(format!(
+                "No {} /{} block {} {}",
+                if self.previous { "previous" } else { "next" },
+                prefix,
+                if self.previous { "before" } else { "after" },
+                if available == 0 { self.ip_object } else { nth(available) }
+            )))
+        }
+        Ok((1..=self.count).map(nth))
+    }
+}
+
+impl MyToolsAddonCommand for NeighbourNetworkCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        Ok(networks_result(&self.neighbours()?.collect::<Vec<IpNetwork>>()))
+    }
+
+    fn execute_to(&self, output: &mut ResultWriter) -> Result<(), MyToolsError> {
+        // Blocks are streamed one by one, so a large count does not need to fit in memory
+        let neighbours = self.neighbours()?;
+        output.begin_list()?;
+        for ip_object in neighbours {
+            if !output.write_item(&CommandResult::field("network", ip_object.to_string()))? {
+                break;
+            }
+        }
+        output.end_list()?;
+        Ok(())
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        fn build(args: &ParsedArgs, previous: bool) -> Result<Box<dyn MyToolsAddonCommand>, MyToolsError> {
+            let ip_object = parsed_network(args)?;
+            let count = args.number::<u128>("count")?.unwrap_or(1);
+            Ok(Box::new(NeighbourNetworkCommand { ip_object, count, previous }))
+        }
+
+        let help = "List the next or previous blocks of the same size (1 by default)";
+        network_specs("next", help, |args| build(args, false))
+            .into_iter()
+            .chain(network_specs("prev", help, |args| build(args, true)))
+            .map(|spec| spec.arg(ArgSpec::optional("count", ValueType::Unsigned)))
+            .collect()
+    }
+}
+
+// Command to get the parent network, a given number of levels up
+struct ParentNetworkCommand {
+    ip_object: IpNetwork,
+    levels: u8,
+}
+
+impl MyToolsAddonCommand for ParentNetworkCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let prefix = self.ip_object.prefix().checked_sub(self.levels)
+            .ok_or(MyToolsError::ExecutionCommandError(format!("{} has no parent {} levels up", self.ip_object, self.levels)))?;
+        let (start, _) = network_bounds(&self.ip_object);
+        let network = start & !host_mask(ip_width(&self.ip_object.ip()), prefix);
+        Ok(CommandResult::field("network", IpNetwork::new(int_to_ip(network, self.ip_object.is_ipv4()), prefix).unwrap().to_string()))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("parent", "Get the network containing this one, a given number of prefix bits shorter (1 by default)", |args| {
+            let ip_object = parsed_network(args)?;
+            let levels = args.number::<u8>("levels")?.unwrap_or(1);
+            Ok(Box::new(ParentNetworkCommand { ip_object, levels }))
+        })
+            .into_iter()
+            .map(|spec| spec.arg(ArgSpec::optional("levels", ValueType::Unsigned)))
+            .collect()
+    }
+}
+
+// Command to get the two halves of a network
+struct ChildrenNetworkCommand {
+    ip_object: IpNetwork,
+}
+
+impl MyToolsAddonCommand for ChildrenNetworkCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let prefix = self.ip_object.prefix();
+        let width = ip_width(&self.ip_object.ip());
+        if prefix == width {
+            return Err(MyToolsError::ExecutionCommandError(format!("{} has no children", self.ip_object)))
+        }
+
+        let is_ipv4 = self.ip_object.is_ipv4();
+        let (start, _) = network_bounds(&self.ip_object);
+        let second = start | (host_mask(width, prefix) ^ host_mask(width, prefix + 1));
+        Ok(networks_result(&[
+            IpNetwork::new(int_to_ip(start, is_ipv4), prefix + 1).unwrap(),
+            IpNetwork::new(int_to_ip(second, is_ipv4), prefix + 1).unwrap(),
+        ]))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("children", "Get the two halves of a network, one prefix bit longer", |args| {
+            Ok(Box::new(ChildrenNetworkCommand { ip_object: parsed_network(args)? }))
+        })
+    }
+}
+
+// Command to find the unallocated space of a pool
+struct FreeSpaceCommand {
+    ip_object: IpNetwork,
+    used: Vec<IpNetwork>,
+    fit: Option<u8>,
+}
+
+impl MyToolsAddonCommand for FreeSpaceCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let is_ipv4 = self.ip_object.is_ipv4();
+        let width = ip_width(&self.ip_object.ip());
+        let used = self.used.iter().map(network_bounds).collect();
+        let free = subtract_ranges(vec![network_bounds(&self.ip_object)], merge_ranges(used));
+
+        let prefix = match self.fit {
+            Some(prefix) => prefix,
+            None => return Ok(networks_result(&ranges_to_cidrs(&free, is_ipv4))),
+        };
+        if prefix < self.ip_object.prefix() || prefix > width {
+            return Err(MyToolsError::ExecutionCommandError(format!("Prefix /{} must be between /{} and /{}", prefix, self.ip_object.prefix(), width)))
+        }
+
+        // First free range holding a block of the requested size, aligned on its size
+        let mask = host_mask(width, prefix);
+        free.iter()
+            .find_map(|(start, end)| {
+                let aligned = if start & mask == 0 { *start } else { (start | mask).checked_add(1)? };
+                (aligned | mask <= *end).then_some(aligned)
+            })
+            .map(|start| CommandResult::field("network", IpNetwork::new(int_to_ip(start, is_ipv4), prefix).unwrap().to_string()))
+            .ok_or(MyToolsError::ExecutionCommandError(format!("No free /{} block left in {}", prefix, self.ip_object)))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        network_specs("free", "List the unallocated prefixes of a pool, or the first free block of a given prefix", |args| {
+            let ip_object = parsed_network(args)?;
+            let fit = args.parsed("fit");
+
+            // Used networks are a comma separated list, or read from a file ("@<file>") or from stdin ("-")
+            let used = args.arg("used");
+            let used = match used.strip_prefix('@') {
+                Some(file) => text_to_ipnetworks(&read_file(file)?)?,
+                None if used == "-" => text_to_ipnetworks(&read_stdin()?)?,
+                None => used
+                    .split(',')
+                    .filter(|arg| !arg.is_empty())
+                    .map(arg_to_ipnetwork_or_host)
+                    .collect::<Result<Vec<IpNetwork>, MyToolsError>>()?,
+            };
+            check_same_family(&ip_object, &used)?;
+
+            Ok(Box::new(FreeSpaceCommand { ip_object, used, fit }))
+        })
+            .into_iter()
+            .map(|spec| spec
+                .option(OptionSpec::value("used", "<ip/cidr,...|@file|->", ValueType::Text, "Allocated networks, a comma separated list, '@<file>' or '-' for stdin").required().stdin())
+                .option(OptionSpec::value("fit", "<prefix>", PREFIX, "Only get the first free block of this prefix")))
+            .collect()
+    }
+}
+
+// Command to build the IPv4-mapped IPv6 address of an IPv4 address
+struct MappedAddressCommand {
+    ip: Ipv4Addr,
+}
+
+impl MyToolsAddonCommand for MappedAddressCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        Ok(CommandResult::field("address", self.ip.to_ipv6_mapped().to_string()))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("mapped", "Build the IPv4-mapped IPv6 address (::ffff:a.b.c.d) of an IPv4 address", |args| {
+                Ok(Box::new(MappedAddressCommand { ip: args.parsed_arg("ipv4")? }))
+            })
+            .arg(ArgSpec::required("ipv4", IPV4))
+        ]
+    }
+}
+
+// Command to build the NAT64 IPv6 address of an IPv4 address (RFC 6052)
+struct Nat64AddressCommand {
+    ip: Ipv4Addr,
+    prefix: IpNetwork,
+}
+
+impl MyToolsAddonCommand for Nat64AddressCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        Ok(CommandResult::field("address", embed_ipv4(&self.prefix, &self.ip).to_string()))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        let help = format!("Build the NAT64 IPv6 address of an IPv4 address (RFC 6052), in the {} prefix by default", NAT64_WELL_KNOWN_PREFIX);
+        vec![
+            CommandSpec::new("nat64", help, |args| {
+                let ip = args.parsed_arg("ipv4")?;
+                let prefix = match args.parsed("ipv6-prefix") {
+                    Some(prefix) => prefix,
+                    None => arg_to_nat64_prefix(NAT64_WELL_KNOWN_PREFIX)?,
+                };
+                Ok(Box::new(Nat64AddressCommand { ip, prefix }))
+            })
+            .arg(ArgSpec::required("ipv4", IPV4))
+            .arg(ArgSpec::optional("ipv6-prefix", NAT64_PREFIX))
+        ]
+    }
+}
+
+// Command to build the 6to4 prefix of an IPv4 address
+struct SixToFourCommand {
+    ip: Ipv4Addr,
+}
+
+impl MyToolsAddonCommand for SixToFourCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let value = (0x2002u128 << 112) | ((u32::from(self.ip) as u128) << 80);
+        Ok(CommandResult::field("prefix", format!("{}/48", Ipv6Addr::from(value))))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("6to4", "Build the 6to4 prefix (2002::/16) of an IPv4 address", |args| {
+                Ok(Box::new(SixToFourCommand { ip: args.parsed_arg("ipv4")? }))
+            })
+            .arg(ArgSpec::required("ipv4", IPV4))
+        ]
+    }
+}
+
+// Command to build a Teredo IPv6 address
+struct TeredoAddressCommand {
+    server: Ipv4Addr,
+    client: Ipv4Addr,
+    port: u16,
+    flags: u16,
+}
+
+impl MyToolsAddonCommand for TeredoAddressCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        // Client port and address are obfuscated by inverting all their bits (RFC 4380)
+        let value = (0x2001_0000u128 << 96)
+            | ((u32::from(self.server) as u128) << 64)
+            | ((self.flags as u128) << 48)
+            | (((!self.port) as u128) << 32)
+            | (!u32::from(self.client)) as u128;
+        Ok(CommandResult::field("address", Ipv6Addr::from(value).to_string()))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("teredo", "Build a Teredo IPv6 address (2001::/32) from its server, client address and port", |args| {
+                let (server, client) = (args.parsed_arg("server-ipv4")?, args.parsed_arg("client-ipv4")?);
+                let port = args.number::<u16>("port")?.unwrap_or_default();
+                let flags = args.parsed("flags").unwrap_or(0);
+                Ok(Box::new(TeredoAddressCommand { server, client, port, flags }))
+            })
+            .arg(ArgSpec::required("server-ipv4", IPV4))
+            .arg(ArgSpec::required("client-ipv4", IPV4))
+            .arg(ArgSpec::required("port", ValueType::Unsigned))
+            .arg(ArgSpec::optional("flags", TEREDO_FLAGS))
+        ]
+    }
+}
+
+// Command to decode the IPv4 address embedded in an IPv6 address
+struct DecodeAddressCommand {
+    ip: Ipv6Addr,
+    nat64_prefix: IpNetwork,
+}
+
+impl MyToolsAddonCommand for DecodeAddressCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let value = u128::from(self.ip);
+        let segments = self.ip.segments();
+
+        let (lines, kind, ipv4): (Vec<(&str, String)>, &str, Ipv4Addr) = if let Some(ip) = self.ip.to_ipv4_mapped() {
+            (vec![("Type", "IPv4-mapped".to_string()), ("IPv4", ip.to_string())], "ipv4-mapped", ip)
+        } else if self.nat64_prefix.contains(IpAddr::V6(self.ip)) {
+            let ip = extract_ipv4(&self.nat64_prefix, &self.ip);
+            (vec![("Type", format!("NAT64 ({})", self.nat64_prefix)), ("IPv4", ip.to_string())], "nat64", ip)
+        } else if segments[0] == 0x2002 {
+            let ip = Ipv4Addr::from((value >> 80) as u32);
+            (vec![("Type", "6to4".to_string()), ("IPv4", ip.to_string())], "6to4", ip)
+        } else if segments[0] == 0x2001 && segments[1] == 0 {
+            // The embedded address of a Teredo address is the public address of its client
+            let ip = Ipv4Addr::from(!(value as u32));
+            (vec![
+                ("Type", "Teredo".to_string()),
+                ("Server", Ipv4Addr::from((value >> 64) as u32).to_string()),
+                ("Client", ip.to_string()),
+                ("Port", (!segments[5]).to_string()),
+                ("Flags", format!("{:#06x}", segments[4])),
+            ], "teredo", ip)
+        } else {
+            return Err(MyToolsError::ExecutionCommandError(format!("No embedded IPv4 address found in {}", self.ip)))
+        };
+
+        // Every key is always present, null when it does not apply to the type
+        let teredo = kind == "teredo";
+        let decoded = CommandValue::object([
+            ("type", kind.into()),
+            ("ipv4", ipv4.to_string().into()),
+            ("nat64_prefix", (kind == "nat64").then(|| self.nat64_prefix.to_string()).into()),
+            ("server", teredo.then(|| Ipv4Addr::from((value >> 64) as u32).to_string()).into()),
+            ("client", teredo.then(|| ipv4.to_string()).into()),
+            ("port", teredo.then_some(!segments[5]).into()),
+            ("flags", teredo.then(|| format!("{:#06x}", segments[4])).into()),
+        ]);
+        Ok(CommandResult::with_text(decoded, lines
+            .iter()
+            .map(|(label, value)| format!("{:<8}{}", format!("{}:", label), value))
+            .collect::<Vec<String>>()
+            .join("\n")))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("decode", "Decode the IPv4 address embedded in an IPv4-mapped, NAT64, 6to4 or Teredo address", |args| {
+                let ip = args.parsed_arg("ipv6")?;
+                let nat64_prefix = match args.parsed("nat64-prefix") {
+                    Some(prefix) => prefix,
+                    None => arg_to_nat64_prefix(NAT64_WELL_KNOWN_PREFIX)?,
+                };
+                Ok(Box::new(DecodeAddressCommand { ip, nat64_prefix }))
+            })
+            .arg(ArgSpec::required("ipv6", IPV6))
+            .arg(ArgSpec::optional("nat64-prefix", NAT64_PREFIX))
+        ]
+    }
+}
+
+// Command to build the SLAAC address of a MAC address in a /64 prefix (modified EUI-64)
+struct Eui64AddressCommand {
+    mac: [u8; 6],
+    prefix: IpNetwork,
+}
+
+impl MyToolsAddonCommand for Eui64AddressCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let network = ip_to_int(&self.prefix.network());
+        Ok(CommandResult::field("address", Ipv6Addr::from(network | eui64_interface_id(&self.mac) as u128).to_string()))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("eui64", "Build the SLAAC address of a MAC address in a /64 prefix (modified EUI-64)", |args| {
+                let mac = args.parsed_arg("mac")?;
+                let prefix = args.parsed_arg("ipv6-prefix/64")?;
+                Ok(Box::new(Eui64AddressCommand { mac, prefix }))
+            })
+            .arg(ArgSpec::required("mac", MAC))
+            .arg(ArgSpec::required("ipv6-prefix/64", SLAAC_PREFIX))
+        ]
+    }
+}
+
+// Command to extract the MAC address of a modified EUI-64 interface identifier
+struct Eui64MacCommand {
+    ip: Ipv6Addr,
+}
+
+impl MyToolsAddonCommand for Eui64MacCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let octets = self.ip.octets();
+        if octets[11] != 0xff || octets[12] != 0xfe {
+            return Err(MyToolsError::ExecutionCommandError(format!("{} does not have an EUI-64 interface identifier (no ff:fe in its middle)", self.ip)))
+        }
+
+        let mac = [octets[8] ^ 0x02, octets[9], octets[10], octets[13], octets[14], octets[15]];
+        Ok(CommandResult::field("mac", mac.iter()
+            .map(|octet| format!("{:02x}", octet))
+            .collect::<Vec<String>>()
+            .join(":")))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("eui64", "Extract the MAC address of an IPv6 address with a modified EUI-64 interface identifier", |args| {
+                Ok(Box::new(Eui64MacCommand { ip: args.parsed_arg("ipv6")? }))
+            })
+            .arg(ArgSpec::required("ipv6", IPV6))
+        ]
+    }
+}
+
+// Command to build the link-local address of a MAC address
+struct LinkLocalAddressCommand {
+    mac: [u8; 6],
+}
+
+impl MyToolsAddonCommand for LinkLocalAddressCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        Ok(CommandResult::field("address", Ipv6Addr::from((0xfe80u128 << 112) | eui64_interface_id(&self.mac) as u128).to_string()))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        vec![
+            CommandSpec::new("link-local", "Build the fe80::/64 link-local address of a MAC address (modified EUI-64)", |args| {
+                Ok(Box::new(LinkLocalAddressCommand { mac: args.parsed_arg("mac")? }))
+            })
+            .arg(ArgSpec::required("mac", MAC))
+        ]
+    }
+}
+
+// Addon structure
+pub struct IpNetworkAddon;
+
+impl MyToolsAddon for IpNetworkAddon {
+    fn get_keyword(&self) -> &'static str {
+        "ipnet"
+    }
+
+    fn get_command_specs(&self) -> Vec<CommandSpec> {
+        // Variants are tried in this order, a network is read as a range only if it is not a CIDR
+        [
+            GetIpAddressCommand::get_command_specs(),
+            GetIpNetmaskCommand::get_command_specs(),
+            GetIpNetworkCommand::get_command_specs(),
+            GetIpWildcardCommand::get_command_specs(),
+            GetIpBinaryCommand::get_command_specs(),
+            GetIpHexCommand::get_command_specs(),
+            GetIpIntCommand::get_command_specs(),
+            ConvertCommand::get_command_specs(),
+            IpInfoCommand::get_command_specs(),
+            SplitNetworkCommand::get_command_specs(),
+            VlsmCommand::get_command_specs(),
+            SummarizeCommand::get_command_specs(),
+            CidrToRangeCommand::get_command_specs(),
+            RangeToCidrsCommand::get_command_specs(),
+            ContainsCommand::get_command_specs(),
+            OverlapCommand::get_command_specs(),
+            ExcludeCommand::get_command_specs(),
+            IntersectCommand::get_command_specs(),
+            UnionCommand::get_command_specs(),
+            HostsCommand::get_command_specs(),
+            NthHostCommand::get_command_specs(),
+            PtrCommand::get_command_specs(),
+            ReverseZonesCommand::get_command_specs(),
+            ClassifyCommand::get_command_specs(),
+            OffsetIpCommand::get_command_specs(),
+            DiffIpCommand::get_command_specs(),
+            NeighbourNetworkCommand::get_command_specs(),
+            ParentNetworkCommand::get_command_specs(),
+            ChildrenNetworkCommand::get_command_specs(),
+            FreeSpaceCommand::get_command_specs(),
+            MappedAddressCommand::get_command_specs(),
+            Nat64AddressCommand::get_command_specs(),
+            SixToFourCommand::get_command_specs(),
+            TeredoAddressCommand::get_command_specs(),
+            DecodeAddressCommand::get_command_specs(),
+            Eui64MacCommand::get_command_specs(),
+            Eui64AddressCommand::get_command_specs(),
+            LinkLocalAddressCommand::get_command_specs(),
+            DiagnoseMaskCommand::get_command_specs(),
+        ]
+        .concat()
+        .into_iter()
+        // Commands reading a list of networks read their whole input, the other ones run once per line
+        .map(|spec| match spec.options.iter().any(|option| option.name == "input") {
+            true => spec,
+            false => spec.lines(),
+        })
+        .collect()
+    }
+}
+
+
+/// Function to parse arguments to a IpNetwork object (IPv4 or IPv6)
+fn arg_to_ipnetwork(arg1: &str, arg2: Option<&str>) -> Result<IpNetwork, MyToolsError> {
+    let (ip, cidr_netmask) = match arg2 {
+        // Handling 2 arguments
+        Some(cidr_netmask) => (arg1, cidr_netmask),
+        // Handling 1 argument, split into ip and cidr/netmask
+        None => arg1.split_once("/").ok_or(MyToolsError::ParseCommandError("Separator '/' is missing".to_string()))?,
+    };
+
+    let ip = ip.parse::<IpAddr>().map_err(|_| MyToolsError::ParseCommandError(format!("Invalid IP address: '{}'", ip)))?;
+    ip_with_mask(ip, arg_to_mask(cidr_netmask)?, cidr_netmask)
+}
+
+/// Prefix length or mask following an address, whose family is only checked against the address
+#[derive(Clone, Copy)]
+enum Mask {
+    Prefix(u8),
+    /// Netmask, wildcard mask or non-contiguous mask
+    Address(IpAddr),
+}
+
+/// Function to parse a prefix length or a mask
+fn arg_to_mask(arg: &str) -> Result<Mask, MyToolsError> {
+    // Check if cidr_netmask is not empty
+    if arg.is_empty() {
+        return Err(MyToolsError::ParseCommandError("Argument is missing a CIDR or a netmask".to_string()))
+    }
+
+    if let Ok(cidr) = arg.parse::<u8>() {
+        return Ok(Mask::Prefix(cidr))
+    }
+    arg.parse::<IpAddr>()
+        .map(Mask::Address)
+        .map_err(|_| MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg)))
+}
+
+/// Function to apply a prefix length or a mask to an address, the mask as written being used in the error messages
+fn ip_with_mask(ip: IpAddr, mask: Mask, arg: &str) -> Result<IpNetwork, MyToolsError> {
+    match mask {
+        // Maximum prefix length depends on the address family
+        Mask::Prefix(cidr) if cidr <= ip_width(&ip) => return Ok(IpNetwork::new(ip, cidr).unwrap()),
+        Mask::Prefix(_) => {},
+        // Netmask or wildcard mask of the same family
+        // (0.0.0.0 and 255.255.255.255 are both, so the prefix length must be written instead)
+        Mask::Address(netmask) if ip.is_ipv4() == netmask.is_ipv4() => {
+            if is_ambiguous_mask(&netmask) {
+                let width = ip_width(&netmask);
+                let prefix = ip_to_int(&netmask).count_ones() as u8;
+                return Err(MyToolsError::ParseCommandError(format!(
+                    "Argument '{}' is ambiguous (netmask of /{} or wildcard mask of /{}), write the prefix length instead",
+                    arg, prefix, width - prefix
+                )))
+            }
+            if is_netmask(&netmask) {
+                return Ok(IpNetwork::with_netmask(ip, netmask).unwrap())
+            }
+            let inverted = int_to_ip(!ip_to_int(&netmask) & host_mask(ip_width(&netmask), 0), netmask.is_ipv4());
+            if is_netmask(&inverted) {
+                return Ok(IpNetwork::with_netmask(ip, inverted).unwrap())
+            }
+
+            // Point to the diagnose command as the mask is non-contiguous
+            return Err(MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask (non-contiguous mask, run 'ipnet diagnose {}' for details)", arg, arg)))
+        },
+        Mask::Address(_) => {},
+    }
+
+    // Return Err in case the mask is neither a valid CIDR nor a valid netmask
+    Err(MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg)))
+}
+
+/// Function to check if a mask is both a netmask and a wildcard mask (all 0 or all 1 bits)
+fn is_ambiguous_mask(mask: &IpAddr) -> bool {
+    let value = ip_to_int(mask);
+    value == 0 || value == host_mask(ip_width(mask), 0)
+}
+
+/// Function to check if an address is a netmask, every 1 bit being before the first 0 bit
+fn is_netmask(addr: &IpAddr) -> bool {
+    let octets: Vec<u8> = match addr {
+        IpAddr::V4(addr) => addr.octets().to_vec(),
+        IpAddr::V6(addr) => addr.octets().to_vec(),
+    };
+    let width = octets.len() * 8;
+    let addr_bit = octets
+        .iter()
+        .fold(0u128, |acc, &octet| acc << 8 | octet as u128);
+
+    let mut found_zero = false;
+    for i in 0..width {
+        if addr_bit & (1 << (width - 1 - i)) == 0 {
+            found_zero = true;
+        } else if found_zero {
+            return false;
+        }
+    }
+    true
+}
+
+/// Function to parse a single IP address
+fn arg_to_ip(arg: &str) -> Result<IpAddr, MyToolsError> {
+    arg.trim()
+        .parse::<IpAddr>()
+        .map_err(|_| MyToolsError::ParseCommandError(format!("Invalid IP address: '{}'", arg)))
+}
+
+/// Function to parse a single IPv6 address
+fn arg_to_ipv6(arg: &str) -> Result<Ipv6Addr, MyToolsError> {
+    match arg_to_ip(arg)? {
+        IpAddr::V6(ip) => Ok(ip),
+        IpAddr::V4(_) => Err(MyToolsError::ParseCommandError(format!("Invalid IPv6 address: '{}'", arg))),
+    }
+}
+
+/// Function to parse a single IPv4 address
+fn arg_to_ipv4(arg: &str) -> Result<Ipv4Addr, MyToolsError> {
+    match arg_to_ip(arg)? {
+        IpAddr::V4(ip) => Ok(ip),
+        IpAddr::V6(_) => Err(MyToolsError::ParseCommandError(format!("Invalid IPv4 address: '{}'", arg))),
+    }
+}
+
+/// Function to parse a NAT64 prefix, whose length must be one of the RFC 6052 lengths
+fn arg_to_nat64_prefix(arg: &str) -> Result<IpNetwork, MyToolsError> {
+    let prefix = arg_to_ipnetwork(arg, None)?;
+    if prefix.is_ipv4() || ![32, 40, 48, 56, 64, 96].contains(&prefix.prefix()) {
+        return Err(MyToolsError::ParseCommandError(format!("Invalid NAT64 prefix: '{}' (must be an IPv6 /32, /40, /48, /56, /64 or /96)", arg)))
+    }
+    Ok(prefix)
+}
+
+/// Function to parse a MAC address ("00:1a:2b:3c:4d:5e", "00-1a-2b-3c-4d-5e" or "001a.2b3c.4d5e")
+fn arg_to_mac(arg: &str) -> Result<[u8; 6], MyToolsError> {
+    let digits: String = arg.chars().filter(|c| !matches!(c, ':' | '-' | '.')).collect();
+    let error = || MyToolsError::ParseCommandError(format!("Invalid MAC address: '{}'", arg));
+    if digits.len() != 12 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(error())
+    }
+
+    let mut mac = [0u8; 6];
+    for (i, octet) in mac.iter_mut().enumerate() {
+        *octet = u8::from_str_radix(&digits[i * 2..i * 2 + 2], 16).map_err(|_| error())?;
+    }
+    Ok(mac)
+}
+
+/// Function to parse an IP address written in the usual notation, as an integer or in hex ("0x...")
+///
+/// Integers up to 4294967295 and hex values up to 8 digits are IPv4 addresses, larger ones are IPv6 addresses.
+/// Only the convert command accepts these forms, elsewhere "10" is a typo rather than 0.0.0.10
+fn arg_to_ip_or_integer(arg: &str) -> Result<IpAddr, MyToolsError> {
+    let arg = arg.trim();
+    if let Ok(ip) = arg.parse::<IpAddr>() {
+        return Ok(ip)
+    }
+
+    let error = || MyToolsError::ParseCommandError(format!("Invalid IP address, integer or hex value: '{}'", arg));
+    let (value, is_ipv4) = match arg.strip_prefix("0x").or(arg.strip_prefix("0X")) {
+        Some(hex) => (u128::from_str_radix(hex, 16).map_err(|_| error())?, hex.len() <= 8),
+        None => {
+            let value = arg.parse::<u128>().map_err(|_| error())?;
+            (value, value <= u32::MAX as u128)
+        },
+    };
+    Ok(int_to_ip(value, is_ipv4))
+}
+
+/// Function to parse a network ("<ip>/<cidr>" or "<ip>/<mask>") or a single host ("<ip>")
+fn arg_to_ipnetwork_or_host(arg: &str) -> Result<IpNetwork, MyToolsError> {
+    if arg.contains('/') {
+        arg_to_ipnetwork(arg, None)
+    } else {
+        arg_to_ip(arg).map(IpNetwork::from)
+    }
+}
+
+/// Function to get the specs of a command taking a network as "<ip/cidr>" or "<ip> <cidr|mask>"
+fn network_specs(path: &'static str, help: &str, build: CommandBuilder) -> Vec<CommandSpec> {
+    vec![
+        CommandSpec::new(path, help, build)
+            .arg(ArgSpec::required("ip/cidr", NETWORK)),
+        CommandSpec::new(path, help, build)
+            .arg(ArgSpec::required("ip", ADDRESS))
+            .arg(ArgSpec::required("cidr|mask", MASK)),
+    ]
+}
+
+/// Function to get the network parsed by one of the specs of network_specs (or of the range command)
+fn parsed_network(args: &ParsedArgs) -> Result<IpNetwork, MyToolsError> {
+    match args.parsed("ip/cidr") {
+        Some(ip_object) => Ok(ip_object),
+        None => ip_with_mask(args.parsed_arg("ip")?, args.parsed_arg("cidr|mask")?, args.arg("cidr|mask")),
+    }
+}
+
+/// Function to parse an inclusive range of addresses written "<start-ip>-<end-ip>"
+fn arg_to_range(arg: &str) -> Result<(IpAddr, IpAddr), MyToolsError> {
+    let (start, end) = arg.split_once('-')
+        .ok_or(MyToolsError::ParseCommandError(format!("Argument '{}' is neither a network nor a range", arg)))?;
+    Ok((arg_to_ip(start)?, arg_to_ip(end)?))
+}
+
+/// Function to parse a prefix length, with or without its leading '/'
+fn arg_to_prefix(arg: &str) -> Result<u8, MyToolsError> {
+    parse_number::<u8>(arg.trim_start_matches('/'), "prefix")
+}
+
+/// Function to parse a SLAAC prefix, which must be an IPv6 /64
+fn arg_to_slaac_prefix(arg: &str) -> Result<IpNetwork, MyToolsError> {
+    let prefix = arg_to_ipnetwork(arg, None)?;
+    if prefix.is_ipv4() || prefix.prefix() != 64 {
+        return Err(MyToolsError::ParseCommandError(format!("Invalid SLAAC prefix: '{}' (must be an IPv6 /64)", arg)))
+    }
+    Ok(prefix)
+}
+
+/// Function to parse a VLSM requirement written "<name>=<hosts>", asking for at least 1 host
+fn arg_to_requirement(arg: &str) -> Result<(String, u128), MyToolsError> {
+    let (name, hosts) = arg.split_once('=')
+        .ok_or(MyToolsError::ParseCommandError(format!("Invalid requirement: '{}' (expected <name>=<hosts>)", arg)))?;
+    match parse_number::<u128>(hosts, "hosts")? {
+        0 => Err(MyToolsError::ParseCommandError(format!("Requirement '{}' must ask for at least 1 host", arg))),
+        hosts => Ok((name.to_string(), hosts)),
+    }
+}
+
+/// Function to parse the flags of a Teredo address, in hex with or without "0x"
+fn arg_to_teredo_flags(arg: &str) -> Result<u16, MyToolsError> {
+    u16::from_str_radix(arg.trim_start_matches("0x"), 16)
+        .map_err(|_| MyToolsError::ParseCommandError(format!("Invalid flags: '{}'", arg)))
+}
+
+/// Function to parse whitespace separated networks, read from stdin or from a file
+fn text_to_ipnetworks(text: &str) -> Result<Vec<IpNetwork>, MyToolsError> {
+    text.split_whitespace()
+        .map(arg_to_ipnetwork_or_host)
+        .collect()
+}
+
+/// Function to read a file given as an argument
+fn read_file(path: &str) -> Result<String, MyToolsError> {
+    std::fs::read_to_string(path)
+        .map_err(|e| MyToolsError::ParseCommandError(format!("Cannot read '{}': {}", path, e)))
+}
+
+/// Function to get the networks of a list command, from its arguments ("-" reading stdin) and from its "--input" file
+fn list_networks(args: &ParsedArgs, name: &str) -> Result<Vec<IpNetwork>, MyToolsError> {
+    let mut ip_objects: Vec<IpNetwork> = Vec::new();
+    for ip_object in args.parsed_values::<Option<IpNetwork>>(name) {
+        match ip_object {
+            Some(ip_object) => ip_objects.push(ip_object),
+            None => ip_objects.extend(text_to_ipnetworks(&read_stdin()?)?),
+        }
+    }
+    if let Some(input) = args.value("input") {
+        ip_objects.extend(text_to_ipnetworks(&read_file(input)?)?);
+    }
+    Ok(ip_objects)
+}
+
+/// Function to check that networks are of the family of the network they are compared to
+fn check_same_family(ip_object: &IpNetwork, others: &[IpNetwork]) -> Result<(), MyToolsError> {
+    match others.iter().find(|other| other.is_ipv4() != ip_object.is_ipv4()) {
+        Some(other) => Err(MyToolsError::ParseCommandError(format!("'{}' and '{}' are not of the same family", other, ip_object))),
+        None => Ok(()),
+    }
+}
+
+/// Function to get the networks of a list command which needs at least one source, an empty stdin or file being an empty list
+fn required_list_networks(args: &ParsedArgs, action: &str) -> Result<Vec<IpNetwork>, MyToolsError> {
+    if args.values("ip/cidr").is_empty() && args.value("input").is_none() {
+        return Err(MyToolsError::InvalidCommand(format!("Missing the networks to {}\n", action)))
+    }
+    list_networks(args, "ip/cidr")
+}
+
+/// Function to get the "--input" option of the list commands, whose file is read as a whole
+fn list_input_option() -> OptionSpec {
+    OptionSpec::value("input", "<file>", ValueType::Text, "Read more networks from a file")
+}
+
+/// Function to get the number of bits of an IP address (32 for IPv4, 128 for IPv6)
+fn ip_width(ip: &IpAddr) -> u8 {
+    match ip {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+/// Function to convert an IP address to its integer value
+fn ip_to_int(ip: &IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(ip) => u32::from(*ip) as u128,
+        IpAddr::V6(ip) => u128::from(*ip),
+    }
+}
+
+/// Function to convert an integer value back to an IP address of the given family
+fn int_to_ip(value: u128, is_ipv4: bool) -> IpAddr {
+    if is_ipv4 {
+        IpAddr::V4(Ipv4Addr::from(value as u32))
+    } else {
+        IpAddr::V6(Ipv6Addr::from(value))
+    }
+}
+
+/// Function to get the host part mask (wildcard) of a prefix, as an integer
+fn host_mask(width: u8, prefix: u8) -> u128 {
+    let host_bits = (width - prefix) as u32;
+    1u128.checked_shl(host_bits).unwrap_or(0).wrapping_sub(1)
+}
+
+/// Usable hosts of a subnet, as integers
+struct HostRange {
+    broadcast: Option<u128>,
+    first: u128,
+    last: u128,
+    /// Number of usable hosts (None for an IPv6 /0)
+    usable: Option<u128>,
+}
+
+/// Function to get the broadcast and usable hosts of a subnet (RFC 3021 for /31 and /32)
+fn host_range(ip_object: &IpNetwork) -> HostRange {
+    let width = ip_width(&ip_object.ip());
+    let host_bits = width - ip_object.prefix();
+    let network_int = ip_to_int(&ip_object.network());
+    let last_int = network_int | host_mask(width, ip_object.prefix());
+    let total_hosts = 1u128.checked_shl(host_bits as u32);
+
+    let (broadcast, first, last, usable) = match (ip_object, host_bits) {
+        (IpNetwork::V4(_), 0) => (None, network_int, last_int, Some(1)),
+        (IpNetwork::V4(_), 1) => (None, network_int, last_int, Some(2)),
+        (IpNetwork::V4(_), _) => (Some(last_int), network_int + 1, last_int - 1, total_hosts.map(|n| n - 2)),
+        (IpNetwork::V6(_), _) => (None, network_int, last_int, total_hosts),
+    };
+    HostRange { broadcast, first, last, usable }
+}
+
+/// Function to get the first and last address of a network, as integers
+fn network_bounds(ip_object: &IpNetwork) -> (u128, u128) {
+    let width = ip_width(&ip_object.ip());
+    let start = ip_to_int(&ip_object.network());
+    (start, start | host_mask(width, ip_object.prefix()))
+}
+
+/// Function to merge overlapping and adjacent ranges, returned sorted
+fn merge_ranges(mut ranges: Vec<(u128, u128)>) -> Vec<(u128, u128)> {
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(u128, u128)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some((_, last_end)) if last_end.checked_add(1).is_none_or(|next| start <= next) => {
+                *last_end = (*last_end).max(end);
+            },
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Function to get the first and last host of a subnet, optionally including the network and broadcast addresses
+fn host_bounds(ip_object: &IpNetwork, include_network_broadcast: bool) -> (u128, u128) {
+    if include_network_broadcast {
+        network_bounds(ip_object)
+    } else {
+        let range = host_range(ip_object);
+        (range.first, range.last)
+    }
+}
+
+/// Function to remove sorted and merged ranges from other sorted and merged ranges
+fn subtract_ranges(ranges: Vec<(u128, u128)>, removed: Vec<(u128, u128)>) -> Vec<(u128, u128)> {
+    let mut result: Vec<(u128, u128)> = Vec::new();
+    let mut removed = removed.into_iter().peekable();
+
+    for (mut start, end) in ranges {
+        let mut remaining = true;
+        while let Some(&(removed_start, removed_end)) = removed.peek() {
+            if removed_start > end {
+                break;
+            }
+            if removed_end >= start {
+                if removed_start > start {
+                    result.push((start, removed_start - 1));
+                }
+                match removed_end.checked_add(1) {
+                    Some(next) if removed_end < end => start = next,
+                    _ => {
+                        remaining = false;
+                        break;
+                    },
+                }
+            }
+            removed.next();
+        }
+        if remaining {
+            result.push((start, end));
+        }
+    }
+    result
+}
+
+/// Function to get the intersection of two sorted and merged lists of ranges
+fn intersect_ranges(a: &[(u128, u128)], b: &[(u128, u128)]) -> Vec<(u128, u128)> {
+    let mut result: Vec<(u128, u128)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        let start = a[i].0.max(b[j].0);
+        let end = a[i].1.min(b[j].1);
+        if start <= end {
+            result.push((start, end));
+        }
+        // Move forward the range ending first
+        if a[i].1 < b[j].1 { i += 1 } else { j += 1 }
+    }
+    result
+}
+
+/// Function to get the minimal list of prefixes covering a list of ranges
+fn ranges_to_cidrs(ranges: &[(u128, u128)], is_ipv4: bool) -> Vec<IpNetwork> {
+    ranges.iter()
+        .flat_map(|(start, end)| range_to_cidrs(*start, *end, is_ipv4))
+        .collect()
+}
+
+/// Function to get the minimal list of prefixes covering an inclusive range
+fn range_to_cidrs(start: u128, end: u128, is_ipv4: bool) -> Vec<IpNetwork> {
+    let width: u32 = if is_ipv4 { 32 } else { 128 };
+    let mut cidrs: Vec<IpNetwork> = Vec::new();
+    let mut current = start;
+
+    loop {
+        // Largest block aligned on the current address that does not go past the end
+        let mut host_bits = current.trailing_zeros().min(width);
+        while host_bits > 0 && current + host_mask(width as u8, (width - host_bits) as u8) > end {
+            host_bits -= 1;
+        }
+        cidrs.push(IpNetwork::new(int_to_ip(current, is_ipv4), (width - host_bits) as u8).unwrap());
+
+        let block_end = current + host_mask(width as u8, (width - host_bits) as u8);
+        match block_end.checked_add(1) {
+            Some(next) if block_end < end => current = next,
+            _ => break,
+        }
+    }
+    cidrs
+}
+
+/// Function to get the smallest prefix containing an inclusive range
+fn supernet_of(start: u128, end: u128, is_ipv4: bool) -> IpNetwork {
+    let width: u32 = if is_ipv4 { 32 } else { 128 };
+    let prefix = ((start ^ end).leading_zeros() - (128 - width)) as u8;
+    let network = start & !host_mask(width as u8, prefix);
+    IpNetwork::new(int_to_ip(network, is_ipv4), prefix).unwrap()
+}
+
+/// Function to read the whole stdin
+#[cfg(not(test))]
+fn read_stdin() -> Result<String, MyToolsError> {
+    std::io::read_to_string(std::io::stdin())
+        .map_err(|e| MyToolsError::ParseCommandError(format!("Cannot read stdin: {}", e)))
+}
+
+// Content read as stdin by the tests
+#[cfg(test)]
+thread_local! {
+    static TEST_STDIN: std::cell::RefCell<String> = const { std::cell::RefCell::new(String::new()) };
+}
+
+/// Function to read the whole stdin, given by the tests
+#[cfg(test)]
+fn read_stdin() -> Result<String, MyToolsError> {
+    Ok(TEST_STDIN.with_borrow_mut(std::mem::take))
+}
+
+/// Function to parse a numeric argument
+fn parse_number<T: FromStr>(arg: &str, name: &str) -> Result<T, MyToolsError> {
+    arg.parse::<T>()
+        .map_err(|_| MyToolsError::ParseCommandError(format!("Invalid {}: '{}'", name, arg)))
+}
+
+/// Function to get the longest prefix whose subnet can hold the given number of usable hosts
+fn prefix_for_hosts(width: u8, hosts: u128) -> Option<u8> {
+    (0..=width).rev().find(|&prefix| {
+        let host_bits = (width - prefix) as u32;
+        let usable = match (width, host_bits) {
+            (32, 0) => 1,
+            (32, 1) => 2,
+            (32, _) => (1u128 << host_bits) - 2,
+            _ => 1u128.checked_shl(host_bits).unwrap_or(u128::MAX),
+        };
+        usable >= hosts
+    })
+}
+
+/// Function to build a list of single-field records, displayed one per line
+fn records(key: &str, values: impl IntoIterator<Item = String>) -> CommandValue {
+    CommandValue::List(values
+        .into_iter()
+        .map(|value| CommandValue::object([(key, value.into())]))
+        .collect())
+}
+
+/// Function to build the result of a list of networks, one per line
+fn networks_result(ip_objects: &[IpNetwork]) -> CommandResult {
+    CommandResult::new(records("network", ip_objects.iter().map(|ip_object| ip_object.to_string())))
+}
+
+/// Function to build the result of labelled fields, displayed one per line with their values aligned
+fn labelled_result(fields: Vec<(&str, &str, CommandValue)>, width: usize) -> CommandResult {
+    let text = fields
+        .iter()
+        .map(|(_, label, value)| {
+            let value = match value {
+                CommandValue::Null => "N/A".to_string(),
+                CommandValue::List(items) => items.iter().map(|item| item.to_text()).collect::<Vec<String>>().join(", "),
+                value => value.to_text(),
+            };
+            format!("{:<width$}{}", format!("{}:", label), value, width = width)
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    CommandResult::with_text(CommandValue::object(fields.into_iter().map(|(key, _, value)| (key, value))), text)
+}
+
+/// Function to format rows as a table with aligned columns
+fn format_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    // Width of each column is the width of its longest cell
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .chain(std::iter::once(header.len()))
+                .max()
+                .unwrap()
+        })
+        .collect();
+
+    let format_row = |row: Vec<&str>| {
+        row.iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    std::iter::once(format_row(headers.to_vec()))
+        .chain(rows.iter().map(|row| format_row(row.iter().map(|cell| cell.as_str()).collect())))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Function to format an IP address in binary, by octets for IPv4 and by 16-bit groups for IPv6
+fn format_binary(ip: &IpAddr) -> String {
+    let width = ip_width(ip) as usize;
+    let bits = format!("{:0width$b}", ip_to_int(ip), width = width);
+    let (group, separator) = if ip.is_ipv4() { (8, ".") } else { (16, ":") };
+
+    bits.as_bytes()
+        .chunks(group)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap())
+        .collect::<Vec<&str>>()
+        .join(separator)
+}
+
+/// Function to get the position of a bit in the output of format_binary
+fn binary_index(bit: u8, is_ipv4: bool) -> usize {
+    let group = if is_ipv4 { 8 } else { 16 };
+    bit as usize + bit as usize / group
+}
+
+/// Function to get the reverse DNS name of the first bits of an IP address
+///
+/// The number of bits is rounded down to octets for IPv4 and to nibbles for IPv6
+fn reverse_name(ip: &IpAddr, bits: u8) -> String {
+    let (labels, suffix): (Vec<String>, &str) = match ip {
+        IpAddr::V4(ip) => (
+            ip.octets()[..bits as usize / 8].iter().map(|octet| octet.to_string()).collect(),
+            "in-addr.arpa"
+        ),
+        IpAddr::V6(ip) => (
+            ip.octets()
+                .iter()
+                .flat_map(|octet| [octet >> 4, octet & 0xf])
+                .take(bits as usize / 4)
+                .map(|nibble| format!("{:x}", nibble))
+                .collect(),
+            "ip6.arpa"
+        ),
+    };
+
+    labels.iter()
+        .rev()
+        .map(|label| label.as_str())
+        .chain(std::iter::once(suffix))
+        .collect::<Vec<&str>>()
+        .join(".")
+}
+
+/// Function to embed an IPv4 address in a NAT64 prefix (RFC 6052), skipping the reserved bits 64 to 71
+fn embed_ipv4(prefix: &IpNetwork, ip: &Ipv4Addr) -> Ipv6Addr {
+    let mut octets = match prefix.network() {
+        IpAddr::V6(network) => network.octets(),
+        IpAddr::V4(_) => unreachable!("NAT64 prefixes are IPv6 prefixes"),
+    };
+    let indexes = (prefix.prefix() as usize / 8..16).filter(|index| *index != 8);
+    for (index, octet) in indexes.zip(ip.octets()) {
+        octets[index] = octet;
+    }
+    Ipv6Addr::from(octets)
+}
+
+/// Function to extract the IPv4 address embedded in a NAT64 address (RFC 6052)
+fn extract_ipv4(prefix: &IpNetwork, ip: &Ipv6Addr) -> Ipv4Addr {
+    let octets = ip.octets();
+    let mut ipv4 = [0u8; 4];
+    let indexes = (prefix.prefix() as usize / 8..16).filter(|index| *index != 8);
+    for (octet, index) in ipv4.iter_mut().zip(indexes) {
+        *octet = octets[index];
+    }
+    Ipv4Addr::from(ipv4)
+}
+
+/// Function to get the modified EUI-64 interface identifier of a MAC address (RFC 4291 appendix A)
+fn eui64_interface_id(mac: &[u8; 6]) -> u64 {
+    // "ff:fe" is inserted in the middle, and the universal/local bit is inverted
+    u64::from_be_bytes([mac[0] ^ 0x02, mac[1], mac[2], 0xff, 0xfe, mac[3], mac[4], mac[5]])
+}
+
+/// Function to get the historical class of an IPv4 address
+fn address_class(ip: &IpAddr) -> Option<&'static str> {
+    match ip {
+        IpAddr::V4(ip) => Some(match ip.octets()[0] {
+            0..=127 => "A",
+            128..=191 => "B",
+            192..=223 => "C",
+            224..=239 => "D",
+            _ => "E",
+        }),
+        IpAddr::V6(_) => None,
+    }
+}
+
+/// Function to get the types (private, loopback, ...) of a network
+fn address_types(ip_object: &IpNetwork) -> Vec<&'static str> {
+    let network = ip_object.network();
+    let mut types: Vec<&'static str> = Vec::new();
+
+    match network {
+        IpAddr::V4(ip) => {
+            if ip.is_private() { types.push("private") }
+            if ip.is_loopback() { types.push("loopback") }
+            if ip.is_link_local() { types.push("link-local") }
+            if ip.is_multicast() { types.push("multicast") }
+            if ip.octets()[0] >= 240 || ip.octets()[0] == 0 { types.push("reserved") }
+        },
+        IpAddr::V6(ip) => {
+            if ip.segments()[0] & 0xfe00 == 0xfc00 { types.push("private") }
+            if ip.is_loopback() { types.push("loopback") }
+            if ip.segments()[0] & 0xffc0 == 0xfe80 { types.push("link-local") }
+            if ip.is_multicast() { types.push("multicast") }
+            if ip.is_unspecified() { types.push("reserved") }
+        },
+    }
+
+    if types.is_empty() {
+        types.push("public");
+    }
+    types
+}
+
+// Tests for arg_to_ipnetwork
+// Tests with 1 argument
+#[test]
+fn arg_to_ipnetwork_test_1_arg_ip_nok() {
+    let arg1 = "this is not an ip/8";
+    let arg_object = arg_to_ipnetwork(arg1, None);
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Invalid IP address: '{}'", arg1.split_once("/").unwrap().0)))
+}
+#[test]
+fn arg_to_ipnetwork_test_1_arg_no_separator() {
+    let arg1 = "127.0.0.1-8";
+    let arg_object = arg_to_ipnetwork(arg1, None);
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError("Separator '/' is missing".to_string()))
+}
+
+#[test]
+fn arg_to_ipnetwork_test_1_arg_ip_ok_cidr_or_netmask_missing_1() {
+    let arg1 = "127.0.0.1";
+    let arg_object = arg_to_ipnetwork(arg1, None);
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError("Separator '/' is missing".to_string()))
+}
+
+#[test]
+fn arg_to_ipnetwork_test_1_arg_ip_ok_cidr_or_netmask_missing_2() {
+    let arg1 = "127.0.0.1/";
+    let arg_object = arg_to_ipnetwork(arg1, None);
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError("Argument is missing a CIDR or a netmask".to_string()))
+}
+
+#[test]
+fn arg_to_ipnetwork_test_1_arg_ip_ok_cidr_ok() {
+    let arg1 = "127.0.0.1/24";
+    let arg_object = arg_to_ipnetwork(arg1, None);
+    assert_eq!(arg_object.unwrap(), IpNetwork::new(IpAddr::from([127, 0, 0, 1]), 24).unwrap())
+}
+
+#[test]
+fn arg_to_ipnetwork_test_1_arg_ip_ok_cidr_nok() {
+    let arg1 = "127.0.0.1/33";
+    let arg_object = arg_to_ipnetwork(arg1, None);
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg1.split_once("/").unwrap().1)))
+}
+
+#[test]
+fn arg_to_ipnetwork_test_1_arg_ip_ok_netmask_ok() {
+    let arg1 = "127.0.0.1/255.0.0.0";
+    let arg_object = arg_to_ipnetwork(arg1, None);
+    assert_eq!(arg_object.unwrap(), IpNetwork::with_netmask(IpAddr::from([127, 0, 0, 1]), IpAddr::from([255, 0, 0, 0])).unwrap())
+}
+
+#[test]
+fn arg_to_ipnetwork_test_1_arg_ip_ok_netmask_nok() {
+    let arg1 = "127.0.0.1/255.255.255.256";
+    let arg_object = arg_to_ipnetwork(arg1, None);
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg1.split_once("/").unwrap().1)))
+}
+
+// Tests with 2 arguments
+#[test]
+fn arg_to_ipnetwork_test_2_args_ip_nok_1() {
+    let arg1 = "this is not an ip";
+    let arg2 = "8";
+    let arg_object = arg_to_ipnetwork(arg1, Some(arg2));
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Invalid IP address: '{}'", arg1)))
+}
+
+#[test]
+fn arg_to_ipnetwork_test_2_args_ip_nok_2() {
+    let arg1 = "127.0.0.256";
+    let arg2 = "8";
+    let arg_object = arg_to_ipnetwork(arg1, Some(arg2));
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Invalid IP address: '{}'", arg1)))
+}
+
+#[test]
+fn arg_to_ipnetwork_test_2_arg_ip_ok_cidr_or_netmask_missing() {
+    let arg1 = "127.0.0.1";
+    let arg2 = "";
+    let arg_object = arg_to_ipnetwork(arg1, Some(arg2));
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError("Argument is missing a CIDR or a netmask".to_string()))
+}
+
+#[test]
+fn arg_to_ipnetwork_test_2_args_ip_ok_cidr_ok() {
+    let arg_object = arg_to_ipnetwork("127.0.0.1", Some("8")).unwrap();
+    let ip_object = IpNetwork::new(IpAddr::from([127, 0, 0, 1]), 8).unwrap();
+    assert_eq!(arg_object, ip_object)
+}
+
+#[test]
+fn arg_to_ipnetwork_test_2_args_ip_ok_cidr_nok() {
+    let arg2 = "33";
+    let arg_object = arg_to_ipnetwork("127.0.0.1", Some(arg2));
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg2)))
+}
+
+#[test]
+fn arg_to_ipnetwork_test_2_args_ip_ok_netmask_ok() {
+    let arg_object = arg_to_ipnetwork("127.0.0.1", Some("255.255.255.0")).unwrap();
+    let ip_object = IpNetwork::with_netmask(IpAddr::from([127, 0, 0, 1]), IpAddr::from([255, 255, 255, 0])).unwrap();
+    assert_eq!(arg_object, ip_object)
+}
+
+#[test]
+fn arg_to_ipnetwork_test_2_args_ip_ok_netmask_nok_1() {
+    let arg2 = "255.255.255.256";
+    let arg_object = arg_to_ipnetwork("127.0.0.1", Some(arg2));
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg2)))
+}
+
+#[test]
+fn arg_to_ipnetwork_test_2_args_ip_ok_netmask_nok_2() {
+    let arg2 = "255.255.0.128";
+    let arg_object = arg_to_ipnetwork("127.0.0.1", Some(arg2));
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask (non-contiguous mask, run 'ipnet diagnose {}' for details)", arg2, arg2)))
+}
+
+// Tests with IPv6 arguments
+#[test]
+fn arg_to_ipnetwork_test_ipv6_1_arg_cidr_ok() {
+    let arg_object = arg_to_ipnetwork("2001:db8::1/48", None).unwrap();
+    let ip_object = IpNetwork::new("2001:db8::1".parse().unwrap(), 48).unwrap();
+    assert_eq!(arg_object, ip_object)
+}
+
+#[test]
+fn arg_to_ipnetwork_test_ipv6_2_args_cidr_ok() {
+    let arg_object = arg_to_ipnetwork("2001:db8::1", Some("128")).unwrap();
+    let ip_object = IpNetwork::new("2001:db8::1".parse().unwrap(), 128).unwrap();
+    assert_eq!(arg_object, ip_object)
+}
+
+#[test]
+fn arg_to_ipnetwork_test_ipv6_cidr_nok() {
+    let arg2 = "129";
+    let arg_object = arg_to_ipnetwork("2001:db8::1", Some(arg2));
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg2)))
+}
+
+#[test]
+fn arg_to_ipnetwork_test_ipv6_netmask_ok() {
+    let arg_object = arg_to_ipnetwork("2001:db8::1/ffff:ffff:ffff::", None).unwrap();
+    let ip_object = IpNetwork::new("2001:db8::1".parse().unwrap(), 48).unwrap();
+    assert_eq!(arg_object, ip_object)
+}
+
+#[test]
+fn arg_to_ipnetwork_test_ipv6_netmask_nok() {
+    let arg2 = "ffff:0:ffff::";
+    let arg_object = arg_to_ipnetwork("2001:db8::1", Some(arg2));
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask (non-contiguous mask, run 'ipnet diagnose {}' for details)", arg2, arg2)))
+}
+
+#[test]
+fn arg_to_ipnetwork_test_mixed_families_nok() {
+    let arg2 = "255.255.0.0";
+    let arg_object = arg_to_ipnetwork("2001:db8::1", Some(arg2));
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask", arg2)))
+}
+
+// Tests with wildcard masks
+#[test]
+fn arg_to_ipnetwork_test_wildcard_ok() {
+    let arg_object = arg_to_ipnetwork("10.0.0.0", Some("0.0.255.255")).unwrap();
+    let ip_object = IpNetwork::new(IpAddr::from([10, 0, 0, 0]), 16).unwrap();
+    assert_eq!(arg_object, ip_object);
+
+    let arg_object = arg_to_ipnetwork("2001:db8::/::ffff:ffff", None).unwrap();
+    let ip_object = IpNetwork::new("2001:db8::".parse().unwrap(), 96).unwrap();
+    assert_eq!(arg_object, ip_object)
+}
+
+#[test]
+fn arg_to_ipnetwork_test_ambiguous_mask_nok() {
+    // In an ACL, "10.1.1.1 0.0.0.0" is a single host, while "0.0.0.0 0.0.0.0" is a default route
+    let arg_object = arg_to_ipnetwork("10.1.1.1", Some("0.0.0.0"));
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError("Argument '0.0.0.0' is ambiguous (netmask of /0 or wildcard mask of /32), write the prefix length instead".to_string()));
+
+    let arg_object = arg_to_ipnetwork("10.1.1.1/255.255.255.255", None);
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError("Argument '255.255.255.255' is ambiguous (netmask of /32 or wildcard mask of /0), write the prefix length instead".to_string()));
+
+    let arg_object = arg_to_ipnetwork("2001:db8::1", Some("::"));
+    assert!(arg_object.is_err());
+    assert_eq!(arg_to_ipnetwork("10.1.1.1", Some("32")).unwrap(), IpNetwork::new(IpAddr::from([10, 1, 1, 1]), 32).unwrap());
+}
+
+#[test]
+fn arg_to_ipnetwork_test_wildcard_nok() {
+    let arg2 = "0.0.255.254";
+    let arg_object = arg_to_ipnetwork("10.0.0.0", Some(arg2));
+    assert_eq!(arg_object.unwrap_err(), MyToolsError::ParseCommandError(format!("Argument '{}' is neither a valid CIDR nor a netmask (non-contiguous mask, run 'ipnet diagnose {}' for details)", arg2, arg2)))
+}
+
+#[test]
+fn command_get_wildcard() {
+    let args = vec!["get".to_string(), "wildcard".to_string(), "172.16.0.0/12".to_string()];
+    assert_eq!(IpNetworkAddon.parse(&args).unwrap().execute().unwrap(), "0.15.255.255");
+    let args = vec!["get".to_string(), "netmask".to_string(), "172.16.0.0".to_string(), "0.15.255.255".to_string()];
+    assert_eq!(IpNetworkAddon.parse(&args).unwrap().execute().unwrap(), "255.240.0.0");
+}
+
+// Tests for the get commands with IPv6 (RFC 5952 output)
+#[test]
+fn command_get_ipv6() {
+    let args = |cmd: &str| vec!["get".to_string(), cmd.to_string(), "2001:0DB8:0000:0000:0000:0000:0000:0001/48".to_string()];
+    let address = IpNetworkAddon.parse(&args("address")).unwrap().execute().unwrap();
+    let netmask = IpNetworkAddon.parse(&args("netmask")).unwrap().execute().unwrap();
+    let network = IpNetworkAddon.parse(&args("network")).unwrap().execute().unwrap();
+    assert_eq!(address, "2001:db8::1");
+    assert_eq!(netmask, "ffff:ffff:ffff::");
+    assert_eq!(network, "2001:db8::");
+}
+
+// Tests for IpInfoCommand
+#[test]
+fn command_info_ipv4() {
+    let args = vec!["info".to_string(), "192.168.1.10/24".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, [
+        "Address:       192.168.1.10",
+        "Netmask:       255.255.255.0",
+        "Wildcard mask: 0.0.0.255",
+        "Network:       192.168.1.0",
+        "Broadcast:     192.168.1.255",
+        "First host:    192.168.1.1",
+        "Last host:     192.168.1.254",
+        "Total hosts:   256",
+        "Usable hosts:  254",
+        "Prefix length: 24",
+        "Address class: C",
+        "Address type:  private",
+    ].join("\n"));
+}
+
+#[test]
+fn command_info_ipv4_31() {
+    let args = vec!["info".to_string(), "8.8.8.8".to_string(), "31".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap().to_string();
+    assert!(result.contains("Broadcast:     N/A"));
+    assert!(result.contains("First host:    8.8.8.8"));
+    assert!(result.contains("Last host:     8.8.8.9"));
+    assert!(result.contains("Usable hosts:  2"));
+    assert!(result.contains("Address type:  public"));
+}
+
+#[test]
+fn command_info_ipv4_32() {
+    let args = vec!["info".to_string(), "127.0.0.1/32".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap().to_string();
+    assert!(result.contains("First host:    127.0.0.1"));
+    assert!(result.contains("Last host:     127.0.0.1"));
+    assert!(result.contains("Usable hosts:  1"));
+    assert!(result.contains("Address type:  loopback"));
+}
+
+#[test]
+fn command_info_ipv6() {
+    let args = vec!["info".to_string(), "fe80::1/64".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap().to_string();
+    assert!(result.contains("Wildcard mask: ::ffff:ffff:ffff:ffff"));
+    assert!(result.contains("Last host:     fe80::ffff:ffff:ffff:ffff"));
+    assert!(result.contains("Total hosts:   18446744073709551616"));
+    assert!(result.contains("Address class: N/A"));
+    assert!(result.contains("Address type:  link-local"));
+}
+
+// Tests for SplitNetworkCommand
+#[test]
+fn command_split_prefix() {
+    let args = vec!["split".to_string(), "10.0.0.0/24".to_string(), "26".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "10.0.0.0/26\n10.0.0.64/26\n10.0.0.128/26\n10.0.0.192/26");
+}
+
+#[test]
+fn command_split_hosts() {
+    let args = vec!["split".to_string(), "10.0.0.0".to_string(), "255.255.255.0".to_string(), "--hosts".to_string(), "60".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "10.0.0.0/26\n10.0.0.64/26\n10.0.0.128/26\n10.0.0.192/26");
+}
+
+#[test]
+fn command_split_capped() {
+    let args: Vec<String> = ["split", "10.0.0.0/8", "/30", "--limit", "2", "--offset", "4"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "10.0.0.16/30\n10.0.0.20/30\n... showing 2 of 4194304 subnets (use --limit and --offset to page)");
+}
+
+#[test]
+fn command_split_ipv6() {
+    let args = vec!["split".to_string(), "2001:db8::/48".to_string(), "50".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "2001:db8::/50\n2001:db8:0:4000::/50\n2001:db8:0:8000::/50\n2001:db8:0:c000::/50");
+}
+
+#[test]
+fn command_split_last_subnet() {
+    let args: Vec<String> = ["split", "::/0", "128", "--offset", "340282366920938463463374607431768211455", "--limit", "2"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff/128\n... showing 1 of 340282366920938463463374607431768211456 subnets (use --limit and --offset to page)");
+}
+
+#[test]
+fn command_split_nok() {
+    let args = vec!["split".to_string(), "10.0.0.0/24".to_string(), "16".to_string()];
+    assert!(IpNetworkAddon.parse(&args).unwrap().execute().is_err());
+    let args = vec!["split".to_string(), "10.0.0.0/24".to_string(), "--hosts".to_string(), "300".to_string()];
+    assert!(IpNetworkAddon.parse(&args).is_err());
+}
+
+#[test]
+fn prefix_for_hosts_test() {
+    assert_eq!(prefix_for_hosts(32, 1), Some(32));
+    assert_eq!(prefix_for_hosts(32, 2), Some(31));
+    assert_eq!(prefix_for_hosts(32, 3), Some(29));
+    assert_eq!(prefix_for_hosts(32, 254), Some(24));
+    assert_eq!(prefix_for_hosts(32, 255), Some(23));
+    assert_eq!(prefix_for_hosts(128, 256), Some(120));
+}
+
+// Tests for VlsmCommand
+#[test]
+fn command_vlsm() {
+    let args: Vec<String> = ["vlsm", "192.168.0.0/24", "users=100", "servers=50", "p2p=2", "printers=10"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, [
+        "Name      Subnet            Usable range                   Hosts  Wasted",
+        "users     192.168.0.0/25    192.168.0.1 - 192.168.0.126    100    26",
+        "servers   192.168.0.128/26  192.168.0.129 - 192.168.0.190  50     12",
+        "printers  192.168.0.192/28  192.168.0.193 - 192.168.0.206  10     4",
+        "p2p       192.168.0.208/31  192.168.0.208 - 192.168.0.209  2      0",
+    ].join("\n"));
+}
+
+#[test]
+fn command_vlsm_does_not_fit() {
+    let args: Vec<String> = ["vlsm", "192.168.0.0", "255.255.255.0", "a=100", "b=100", "c=100"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute();
+    assert_eq!(result.unwrap_err(), MyToolsError::ExecutionCommandError("Requirements do not fit in 192.168.0.0/24: no room left for 'c' (100 hosts)".to_string()));
+}
+
+#[test]
+fn command_vlsm_nok() {
+    let args: Vec<String> = ["vlsm", "192.168.0.0/24", "a=0"].iter().map(|s| s.to_string()).collect();
+    assert!(IpNetworkAddon.parse(&args).is_err());
+    let args: Vec<String> = ["vlsm", "192.168.0.0/24"].iter().map(|s| s.to_string()).collect();
+    assert!(IpNetworkAddon.parse(&args).is_err());
+}
+
+// Tests for SummarizeCommand
+#[test]
+fn command_summarize() {
+    let args: Vec<String> = ["summarize", "10.0.1.0/24", "10.0.0.0/24", "10.0.2.0/25", "10.0.2.128/25", "10.0.0.64/26", "10.0.4.1", "2001:db8::/33", "2001:db8:8000::/33"]
+        .iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "10.0.0.0/23\n10.0.2.0/24\n10.0.4.1/32\n2001:db8::/32");
+}
+
+#[test]
+fn command_summarize_supernet() {
+    let args: Vec<String> = ["summarize", "--supernet", "10.0.1.0/24", "10.0.2.0/24"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "10.0.0.0/22");
+
+    let args: Vec<String> = ["summarize", "--supernet", "10.0.1.0/24", "::/0"].iter().map(|s| s.to_string()).collect();
+    assert!(IpNetworkAddon.parse(&args).unwrap().execute().is_err());
+}
+
+#[test]
+fn range_to_cidrs_test() {
+    let cidrs = range_to_cidrs(ip_to_int(&"10.0.0.1".parse().unwrap()), ip_to_int(&"10.0.0.10".parse().unwrap()), true);
+    let cidrs: Vec<String> = cidrs.iter().map(|cidr| cidr.to_string()).collect();
+    assert_eq!(cidrs, vec!["10.0.0.1/32", "10.0.0.2/31", "10.0.0.4/30", "10.0.0.8/31", "10.0.0.10/32"]);
+
+    let cidrs = range_to_cidrs(0, u128::MAX, false);
+    assert_eq!(cidrs, vec![IpNetwork::new("::".parse().unwrap(), 0).unwrap()]);
+}
+
+#[test]
+fn merge_ranges_test() {
+    assert_eq!(merge_ranges(vec![(5, 9), (0, 3), (4, 4), (20, 30), (25, 26), (u128::MAX, u128::MAX)]), vec![(0, 9), (20, 30), (u128::MAX, u128::MAX)]);
+}
+
+// Tests for RangeToCidrsCommand and CidrToRangeCommand
+#[test]
+fn command_range_to_cidrs() {
+    let args = vec!["range".to_string(), "192.168.0.10".to_string(), "192.168.0.20".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "192.168.0.10/31\n192.168.0.12/30\n192.168.0.16/30\n192.168.0.20/32");
+
+    let args = vec!["range".to_string(), "2001:db8::-2001:db8::ffff".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "2001:db8::/112");
+}
+
+#[test]
+fn command_range_to_cidrs_nok() {
+    let args = vec!["range".to_string(), "192.168.0.20".to_string(), "192.168.0.10".to_string()];
+    assert!(IpNetworkAddon.parse(&args).unwrap().execute().is_err());
+    let args = vec!["range".to_string(), "192.168.0.20".to_string(), "::1".to_string()];
+    assert!(IpNetworkAddon.parse(&args).unwrap().execute().is_err());
+    let args = vec!["range".to_string(), "192.168.0.20".to_string()];
+    assert!(IpNetworkAddon.parse(&args).is_err());
+}
+
+#[test]
+fn command_cidr_to_range() {
+    let args = vec!["range".to_string(), "10.1.2.3/255.255.0.0".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "10.1.0.0 - 10.1.255.255");
+
+    let args = vec!["range".to_string(), "2001:db8::1".to_string(), "64".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "2001:db8:: - 2001:db8::ffff:ffff:ffff:ffff");
+}
+
+// Tests for ContainsCommand
+#[test]
+fn command_contains() {
+    let args = vec!["contains".to_string(), "10.0.0.0/8".to_string(), "10.1.2.3".to_string()];
+    assert_eq!(IpNetworkAddon.parse(&args).unwrap().execute().unwrap(), "yes");
+    let args = vec!["contains".to_string(), "10.0.0.0/8".to_string(), "10.1.0.0/16".to_string()];
+    assert_eq!(IpNetworkAddon.parse(&args).unwrap().execute().unwrap(), "yes");
+    let args = vec!["contains".to_string(), "10.1.0.0/16".to_string(), "10.0.0.0/8".to_string()];
+    assert_eq!(IpNetworkAddon.parse(&args).unwrap().execute().unwrap_err(), MyToolsError::NegativeResult(CommandResult::field("contains", false)));
+    let args = vec!["contains".to_string(), "::/0".to_string(), "10.0.0.1".to_string()];
+    assert_eq!(IpNetworkAddon.parse(&args).unwrap().execute().unwrap_err(), MyToolsError::NegativeResult(CommandResult::field("contains", false)));
+}
+
+// Tests for OverlapCommand
+#[test]
+fn command_overlap() {
+    let args: Vec<String> = ["overlap", "10.0.0.0/24", "10.1.0.0/16", "10.0.0.128/25", "10.1.5.0/24", "192.168.0.0/24"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, [
+        "yes",
+        "10.0.0.0/24 and 10.0.0.128/25 overlap: 10.0.0.128 - 10.0.0.255",
+        "10.1.0.0/16 and 10.1.5.0/24 overlap: 10.1.5.0 - 10.1.5.255",
+    ].join("\n"));
+}
+
+#[test]
+fn command_overlap_none() {
+    let args: Vec<String> = ["overlap", "10.0.0.0/24", "10.0.1.0/24", "::/0"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute();
+    assert_eq!(result.unwrap_err(), MyToolsError::NegativeResult(CommandResult::with_text(CommandValue::List(Vec::new()), "no")));
+}
+
+// Tests for ExcludeCommand, IntersectCommand and UnionCommand
+#[test]
+fn command_exclude() {
+    let args: Vec<String> = ["exclude", "10.0.0.0/16", "10.0.5.0/24"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "10.0.0.0/22\n10.0.4.0/24\n10.0.6.0/23\n10.0.8.0/21\n10.0.16.0/20\n10.0.32.0/19\n10.0.64.0/18\n10.0.128.0/17");
+
+    let args: Vec<String> = ["exclude", "2001:db8::/32", "2001:db8::/33", "2001:db8:c000::/34"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "2001:db8:8000::/34");
+
+    let args: Vec<String> = ["exclude", "2001:db8::/32", "2001:db8::/33", "10.0.0.0/8"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(IpNetworkAddon.parse(&args).err().unwrap(), MyToolsError::ParseCommandError("'10.0.0.0/8' and '2001:db8::/32' are not of the same family".to_string()));
+
+    let args: Vec<String> = ["exclude", "10.0.0.0/24", "10.0.0.0/8"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "");
+}
+
+#[test]
+fn command_intersect() {
+    let args: Vec<String> = ["intersect", "10.0.0.0/8", "10.1.0.0/16", "10.1.2.0/24"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "10.1.2.0/24");
+
+    let args: Vec<String> = ["intersect", "10.0.0.0/16", "10.1.0.0/16"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "");
+
+    // An empty stdin gives no networks
+    let result = IntersectCommand { ip_objects: Vec::new() }.execute().unwrap();
+    assert_eq!(result, "");
+}
+
+#[test]
+fn command_union() {
+    let args: Vec<String> = ["union", "10.0.0.0/24", "fd00::/8", "10.0.1.0/24", "10.0.0.5"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "10.0.0.0/23\nfd00::/8");
+}
+
+#[test]
+fn subtract_ranges_test() {
+    assert_eq!(subtract_ranges(vec![(0, 10), (20, 30)], vec![(0, 2), (5, 5), (9, 21), (30, 40)]), vec![(3, 4), (6, 8), (22, 29)]);
+    assert_eq!(subtract_ranges(vec![(0, u128::MAX)], vec![(1, u128::MAX)]), vec![(0, 0)]);
+}
+
+/// Output closed after a number of writes (like a pipe to `head`), keeping what was written before
+#[cfg(test)]
+struct ClosedAfter {
+    writes: usize,
+    written: Vec<u8>,
+}
+
+#[cfg(test)]
+impl ClosedAfter {
+    /// Function to run a command until the output is closed, and get what it wrote
+    fn run(args: &[&str], writes: usize) -> String {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        let cmd = IpNetworkAddon.parse(&args).unwrap();
+        let mut output = ClosedAfter { writes, written: Vec::new() };
+        cmd.execute_to(&mut ResultWriter::new(&mut output, OutputFormat::Text)).unwrap();
+        String::from_utf8(output.written).unwrap()
+    }
+}
+
+#[cfg(test)]
+impl std::io::Write for ClosedAfter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.writes == 0 {
+            return Err(std::io::ErrorKind::BrokenPipe.into())
+        }
+        self.writes -= 1;
+        self.written.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+}
+
+// Tests for HostsCommand
+#[test]
+fn command_hosts() {
+    let args: Vec<String> = ["hosts", "192.168.0.0/29"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "192.168.0.1\n192.168.0.2\n192.168.0.3\n192.168.0.4\n192.168.0.5\n192.168.0.6");
+}
+
+#[test]
+fn command_hosts_options() {
+    let args: Vec<String> = ["hosts", "192.168.0.0", "255.255.255.248", "--include-network-broadcast", "--offset", "1", "--step", "3", "--limit", "2"]
+        .iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "192.168.0.1\n192.168.0.4");
+
+    let args: Vec<String> = ["hosts", "::/0", "--offset", "340282366920938463463374607431768211454", "--step", "10"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "ffff:ffff:ffff:ffff:ffff:ffff:ffff:fffe");
+}
+
+#[test]
+fn command_hosts_streamed() {
+    // Each host is written as soon as it is listed, and the listing stops once the output is closed
+    assert_eq!(ClosedAfter::run(&["hosts", "10.0.0.0/8"], 3), "10.0.0.1\n10.0.0.2\n10.0.0.3\n");
+    // Buffering the 2^128 hosts of ::/0 would never finish
+    assert_eq!(ClosedAfter::run(&["hosts", "::/0", "--offset", "1"], 2), "::1\n::2\n");
+}
+
+// Tests for NthHostCommand
+#[test]
+fn command_nth() {
+    let nth = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        IpNetworkAddon.parse(&args).unwrap().execute()
+    };
+    assert_eq!(nth(&["nth", "10.0.0.0/24", "0"]).unwrap(), "10.0.0.1");
+    assert_eq!(nth(&["nth", "10.0.0.0/24", "9"]).unwrap(), "10.0.0.10");
+    assert_eq!(nth(&["nth", "10.0.0.0/24", "-1"]).unwrap(), "10.0.0.254");
+    assert_eq!(nth(&["nth", "10.0.0.0", "24", "-1", "--include-network-broadcast"]).unwrap(), "10.0.0.255");
+    assert_eq!(nth(&["nth", "2001:db8::/64", "-1"]).unwrap(), "2001:db8::ffff:ffff:ffff:ffff");
+    assert!(nth(&["nth", "10.0.0.0/24", "254"]).is_err());
+    assert!(nth(&["nth", "10.0.0.0/24", "-255"]).is_err());
+}
+
+// Tests for DiagnoseMaskCommand
+#[test]
+fn command_diagnose() {
+    let args = vec!["diagnose".to_string(), "255.255.0.128".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, [
+        "Mask:          255.255.0.128",
+        "Binary:        11111111.11111111.00000000.10000000",
+        "                                 ^",
+        "Valid:         no, bit 17 is 0 but is followed by 1 bits",
+        "Nearest below: 255.255.0.0 (/16)",
+        "Nearest above: 255.255.255.128 (/25)",
+    ].join("\n"));
+}
+
+#[test]
+fn command_diagnose_valid() {
+    let args = vec!["diagnose".to_string(), "255.255.254.0".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap().to_string();
+    assert!(result.ends_with("Valid:         yes, /23"));
+
+    let args = vec!["diagnose".to_string(), "ffff:0:ffff::".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap().to_string();
+    assert!(result.contains("Nearest below: ffff:: (/16)"));
+    assert!(result.contains("Nearest above: ffff:ffff:ffff:: (/48)"));
+}
+
+// Tests for ConvertCommand
+#[test]
+fn command_convert() {
+    let expected = [
+        "Dotted:  192.168.1.10",
+        "Integer: 3232235786",
+        "Hex:     0xc0a8010a",
+        "Octal:   030052000412",
+        "Binary:  11000000.10101000.00000001.00001010",
+    ].join("\n");
+    for input in ["192.168.1.10", "3232235786", "0xC0A8010A"] {
+        let args = vec!["convert".to_string(), input.to_string()];
+        assert_eq!(IpNetworkAddon.parse(&args).unwrap().execute().unwrap(), expected);
+    }
+
+    let args = vec!["convert".to_string(), "0x20010db8000000000000000000000001".to_string()];
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap().to_string();
+    assert!(result.starts_with("Address: 2001:db8::1\nInteger: 42540766411282592856903984951653826561"));
+}
+
+// Tests for GetIpBinaryCommand, GetIpHexCommand and GetIpIntCommand
+#[test]
+fn command_get_binary_hex_int() {
+    let get = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        IpNetworkAddon.parse(&args).unwrap().execute().unwrap()
+    };
+    assert_eq!(get(&["get", "binary", "192.168.1.10/24"]), "11000000.10101000.00000001|00001010");
+    assert_eq!(get(&["get", "binary", "192.168.1.10", "255.255.240.0"]), "11000000.10101000.0000|0001.00001010");
+    assert_eq!(get(&["get", "binary", "192.168.1.10/32"]), "11000000.10101000.00000001.00001010|");
+    assert_eq!(get(&["get", "hex", "192.168.1.10/24"]), "0xc0a8010a");
+    assert_eq!(get(&["get", "int", "192.168.1.10/24"]), "3232235786");
+
+    // Integers and hex values are only addresses for the convert command
+    for args in [["get", "network", "10/8"], ["get", "hex", "0xc0a8010a/24"]] {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        assert!(IpNetworkAddon.parse(&args).is_err());
+    }
+}
+
+// Tests for PtrCommand
+#[test]
+fn command_ptr() {
+    let args = vec!["ptr".to_string(), "192.168.1.10".to_string()];
+    assert_eq!(IpNetworkAddon.parse(&args).unwrap().execute().unwrap(), "10.1.168.192.in-addr.arpa");
+    let args = vec!["ptr".to_string(), "2001:db8::567:89ab".to_string()];
+    assert_eq!(IpNetworkAddon.parse(&args).unwrap().execute().unwrap(), "b.a.9.8.7.6.5.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa");
+}
+
+// Tests for ReverseZonesCommand
+#[test]
+fn command_rdns_zones() {
+    let zones = |arg: &str| {
+        let args = vec!["rdns-zones".to_string(), arg.to_string()];
+        IpNetworkAddon.parse(&args).unwrap().execute().unwrap()
+    };
+    assert_eq!(zones("10.0.0.0/8"), "10.in-addr.arpa");
+    assert_eq!(zones("10.20.0.0/22"), "0.20.10.in-addr.arpa\n1.20.10.in-addr.arpa\n2.20.10.in-addr.arpa\n3.20.10.in-addr.arpa");
+    assert_eq!(zones("192.168.1.64/26"), "64/26.1.168.192.in-addr.arpa");
+    assert_eq!(zones("0.0.0.0/0"), "in-addr.arpa");
+    assert_eq!(zones("2001:db8::/31"), "8.b.d.0.1.0.0.2.ip6.arpa\n9.b.d.0.1.0.0.2.ip6.arpa");
+}
+
+// Tests for ClassifyCommand
+#[test]
+fn command_classify() {
+    let classify = |arg: &str| {
+        let args = vec!["classify".to_string(), arg.to_string()];
+        IpNetworkAddon.parse(&args).unwrap().execute().unwrap().to_string()
+    };
+    assert_eq!(classify("100.64.1.1"), "Prefix         Name                          RFC       Coverage\n100.64.0.0/10  Shared Address Space (CGNAT)  RFC 6598  full");
+    assert_eq!(classify("8.8.8.8"), "8.8.8.8/32 is not in any special-purpose registry entry");
+    assert!(classify("fd12:3456::/48").contains("Unique-Local (ULA)"));
+    assert!(classify("2002:c000:0204::1").contains("6to4"));
+    assert_eq!(classify("3fff:fff::1"), "Prefix     Name           RFC       Coverage\n3fff::/20  Documentation  RFC 9637  full");
+    assert!(classify("5f00:1:2::/48").contains("Segment Routing (SRv6) SIDs"));
+
+    let result = classify("192.0.0.0/23");
+    assert!(result.contains("192.0.0.0/24    IETF Protocol Assignments"));
+    assert!(result.contains("192.0.0.0/29    IPv4 Service Continuity Prefix"));
+    assert!(result.lines().skip(1).all(|line| line.ends_with("partial")));
+}
+
+#[test]
+fn special_purpose_registry_is_valid() {
+    for (prefix, _, _) in SPECIAL_PURPOSE_REGISTRY {
+        let entry = prefix.parse::<IpNetwork>().unwrap();
+        assert_eq!(entry.ip(), entry.network(), "{} is not a network address", prefix);
+    }
+
+    // Entries are sorted, IPv4 first, by address then by prefix length
+    let entries: Vec<(bool, u128, u8)> = SPECIAL_PURPOSE_REGISTRY
+        .iter()
+        .map(|(prefix, _, _)| prefix.parse::<IpNetwork>().unwrap())
+        .map(|entry| (entry.is_ipv6(), ip_to_int(&entry.ip()), entry.prefix()))
+        .collect();
+    assert!(entries.windows(2).all(|pair| pair[0] < pair[1]));
+    for prefix in ["3fff::/20", "5f00::/16"] {
+        assert!(SPECIAL_PURPOSE_REGISTRY.iter().any(|(entry, _, _)| *entry == prefix), "{} is missing", prefix);
+    }
+}
+
+// Tests for the commands run per line and the list commands reading a file
+#[test]
+fn command_bulk_input_file() {
+    let path = std::env::temp_dir().join("my_tools_ipnet_bulk_input_file.txt");
+    std::fs::write(&path, "10.0.0.1/8\n\n# comment\n172.16.5.4 255.255.0.0\n2001:db8::1/32\n").unwrap();
+
+    let args: Vec<String> = ["get", "network", "--input", path.to_str().unwrap()].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "10.0.0.0\n172.16.0.0\n2001:db8::");
+
+    let args: Vec<String> = ["nth", "-", "-1", "--input", path.to_str().unwrap()].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "10.255.255.254\n172.16.255.254\n2001:db8:ffff:ffff:ffff:ffff:ffff:ffff");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn command_bulk_errors() {
+    let path = std::env::temp_dir().join("my_tools_ipnet_bulk_errors.txt");
+    std::fs::write(&path, "10.0.0.1/8\nnot-an-ip/8\n10.0.0.1/33\n192.168.0.1/24\n").unwrap();
+
+    let args: Vec<String> = ["get", "network", "--input", path.to_str().unwrap()].iter().map(|s| s.to_string()).collect();
+    let mut output: Vec<u8> = Vec::new();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute_to(&mut ResultWriter::new(&mut output, OutputFormat::Text));
+    assert_eq!(String::from_utf8(output).unwrap(), [
+        "10.0.0.0",
+        "line 2: Error while parsing command: Invalid IP address: 'not-an-ip'",
+        "line 3: Error while parsing command: Argument '33' is neither a valid CIDR nor a netmask",
+        "192.168.0.0\n",
+    ].join("\n"));
+    assert!(matches!(result, Err(MyToolsError::NegativeResult(result)) if result == "2 of 4 lines failed"));
+
+    // Failed lines are items of the structured output too
+    let mut output: Vec<u8> = Vec::new();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute_to(&mut ResultWriter::new(&mut output, OutputFormat::Json));
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("\"line\": 2,\n    \"input\": \"not-an-ip/8\",\n    \"error\": \"Error while parsing command: Invalid IP address: 'not-an-ip'\""));
+    assert!(matches!(result, Err(MyToolsError::NegativeResult(_))));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn command_bulk_option_value() {
+    // "-" as the value of an option is read by the option, the command runs once
+    TEST_STDIN.set("10.0.0.0/26\n10.0.0.128/27\n".to_string());
+    let args: Vec<String> = ["free", "10.0.0.0/24", "--used", "-"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "10.0.0.64/26\n10.0.0.160/27\n10.0.0.192/26");
+}
+
+#[test]
+fn command_bulk_list_command() {
+    let path = std::env::temp_dir().join("my_tools_ipnet_bulk_list_command.txt");
+    std::fs::write(&path, "10.0.0.0/24\n10.0.1.0/24\n").unwrap();
+
+    let args: Vec<String> = ["summarize", "--input", path.to_str().unwrap()].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "10.0.0.0/23");
+    std::fs::remove_file(&path).unwrap();
+}
+
+// Tests for OffsetIpCommand
+#[test]
+fn command_add_sub() {
+    let run = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        IpNetworkAddon.parse(&args).unwrap().execute()
+    };
+    assert_eq!(run(&["add", "10.0.0.250", "10"]).unwrap(), "10.0.1.4");
+    assert_eq!(run(&["sub", "10.0.1.4", "10"]).unwrap(), "10.0.0.250");
+    assert_eq!(run(&["add", "2001:db8::ffff", "1"]).unwrap(), "2001:db8::1:0");
+    assert_eq!(run(&["add", "255.255.255.250", "6"]).unwrap_err(), MyToolsError::ExecutionCommandError("255.255.255.250 + 6 is out of the IPv4 address space".to_string()));
+    assert_eq!(run(&["sub", "0.0.0.5", "6"]).unwrap_err(), MyToolsError::ExecutionCommandError("0.0.0.5 - 6 is out of the IPv4 address space".to_string()));
+    assert!(run(&["add", "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff", "1"]).is_err());
+}
+
+// Tests for DiffIpCommand
+#[test]
+fn command_diff() {
+    let run = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        IpNetworkAddon.parse(&args).unwrap().execute()
+    };
+    assert_eq!(run(&["diff", "10.0.0.1", "10.0.1.1"]).unwrap(), "256");
+    assert_eq!(run(&["diff", "10.0.1.1", "10.0.0.1"]).unwrap(), "-256");
+    assert!(run(&["diff", "10.0.1.1", "::1"]).is_err());
+}
+
+// Tests for NeighbourNetworkCommand
+#[test]
+fn command_next_prev() {
+    let run = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        IpNetworkAddon.parse(&args).unwrap().execute()
+    };
+    assert_eq!(run(&["next", "10.0.0.5/24"]).unwrap(), "10.0.1.0/24");
+    assert_eq!(run(&["next", "10.0.0.0", "255.255.255.192", "3"]).unwrap(), "10.0.0.64/26\n10.0.0.128/26\n10.0.0.192/26");
+    assert_eq!(run(&["prev", "10.0.1.0/24", "2"]).unwrap(), "10.0.0.0/24\n9.255.255.0/24");
+    assert_eq!(run(&["prev", "2001:db8:1::/48"]).unwrap(), "2001:db8::/48");
+    assert_eq!(run(&["next", "255.255.255.0/24"]).unwrap_err(), MyToolsError::ExecutionCommandError("No next /24 block after 255.255.255.0/24".to_string()));
+    assert_eq!(run(&["prev", "0.0.0.0/24"]).unwrap_err(), MyToolsError::ExecutionCommandError("No previous /24 block before 0.0.0.0/24".to_string()));
+    assert!(run(&["prev", "0.0.1.0/24", "2"]).is_err());
+    assert_eq!(run(&["next", "10.0.0.5/24", "4294967295"]).unwrap_err(), MyToolsError::ExecutionCommandError("No next /24 block after 255.255.255.0/24".to_string()));
+}
+
+#[test]
+fn command_next_streamed() {
+    // A huge count is listed block by block, until the output is closed
+    assert_eq!(ClosedAfter::run(&["next", "::/128", "1000000000000"], 3), "::1/128\n::2/128\n::3/128\n");
+}
+
+// Tests for ParentNetworkCommand and ChildrenNetworkCommand
+#[test]
+fn command_parent_children() {
+    let run = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        IpNetworkAddon.parse(&args).unwrap().execute()
+    };
+    assert_eq!(run(&["parent", "10.0.1.0/24"]).unwrap(), "10.0.0.0/23");
+    assert_eq!(run(&["parent", "10.0.1.0", "24", "8"]).unwrap(), "10.0.0.0/16");
+    assert!(run(&["parent", "10.0.1.0/24", "25"]).is_err());
+    assert_eq!(run(&["children", "10.0.0.0/23"]).unwrap(), "10.0.0.0/24\n10.0.1.0/24");
+    assert_eq!(run(&["children", "::/0"]).unwrap(), "::/1\n8000::/1");
+    assert!(run(&["children", "10.0.0.1/32"]).is_err());
+}
+
+// Tests for FreeSpaceCommand
+#[test]
+fn command_free() {
+    let args: Vec<String> = ["free", "10.0.0.0/24", "--used", "10.0.0.0/26,10.0.0.128/27,10.0.0.200"].iter().map(|s| s.to_string()).collect();
+    let result = IpNetworkAddon.parse(&args).unwrap().execute().unwrap();
+    assert_eq!(result, "10.0.0.64/26\n10.0.0.160/27\n10.0.0.192/29\n10.0.0.201/32\n10.0.0.202/31\n10.0.0.204/30\n10.0.0.208/28\n10.0.0.224/27");
+
+    let args: Vec<String> = ["free", "10.0.0.0/24", "--used", "10.0.0.0/26,2001:db8::/64"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(IpNetworkAddon.parse(&args).err().unwrap(), MyToolsError::ParseCommandError("'2001:db8::/64' and '10.0.0.0/24' are not of the same family".to_string()));
+}
+
+#[test]
+fn command_free_fit() {
+    let path = std::env::temp_dir().join("my_tools_ipnet_free_fit.txt");
+    std::fs::write(&path, "10.0.0.0/26\n10.0.0.128/27\n10.0.0.200\n").unwrap();
+
+    let run = |fit: &str| {
+        let used = format!("@{}", path.display());
+        let args: Vec<String> = ["free", "10.0.0.0", "24", "--used", &used, "--fit", fit].iter().map(|s| s.to_string()).collect();
+        IpNetworkAddon.parse(&args).unwrap().execute()
+    };
+    assert_eq!(run("26").unwrap(), "10.0.0.64/26");
+    assert_eq!(run("/27").unwrap(), "10.0.0.64/27");
+    assert_eq!(run("29").unwrap(), "10.0.0.64/29");
+    assert_eq!(run("25").unwrap_err(), MyToolsError::ExecutionCommandError("No free /25 block left in 10.0.0.0/24".to_string()));
+    std::fs::remove_file(&path).unwrap();
+
+    // A missing file is an error, it is never read as a list
+    let args: Vec<String> = ["free", "10.0.0.0/24", "--used", "@10.0.0.0/26"].iter().map(|s| s.to_string()).collect();
+    assert!(IpNetworkAddon.parse(&args).is_err());
+
+    let args: Vec<String> = ["free", "10.0.0.0/24", "--used", "10.0.0.0/25,10.0.0.192/28", "--fit", "27"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(IpNetworkAddon.parse(&args).unwrap().execute().unwrap(), "10.0.0.128/27");
+    let args: Vec<String> = ["free", "10.0.0.0/24", "--used", "10.0.0.0/25,10.0.0.130", "--fit", "27"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(IpNetworkAddon.parse(&args).unwrap().execute().unwrap(), "10.0.0.160/27");
+}
+
+// Tests for MappedAddressCommand, Nat64AddressCommand, SixToFourCommand and TeredoAddressCommand
+#[test]
+fn command_transition_build() {
+    let run = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        IpNetworkAddon.parse(&args).unwrap().execute()
+    };
+    assert_eq!(run(&["mapped", "192.0.2.33"]).unwrap(), "::ffff:192.0.2.33");
+    assert_eq!(run(&["nat64", "192.0.2.33"]).unwrap(), "64:ff9b::c000:221");
+    // RFC 6052 section 2.4 examples
+    assert_eq!(run(&["nat64", "192.0.2.33", "2001:db8::/32"]).unwrap(), "2001:db8:c000:221::");
+    assert_eq!(run(&["nat64", "192.0.2.33", "2001:db8:100::/40"]).unwrap(), "2001:db8:1c0:2:21::");
+    assert_eq!(run(&["nat64", "192.0.2.33", "2001:db8:122::/48"]).unwrap(), "2001:db8:122:c000:2:2100::");
+    assert_eq!(run(&["nat64", "192.0.2.33", "2001:db8:122:300::/56"]).unwrap(), "2001:db8:122:3c0:0:221::");
+    assert_eq!(run(&["nat64", "192.0.2.33", "2001:db8:122:344::/64"]).unwrap(), "2001:db8:122:344:c0:2:2100:0");
+    assert_eq!(run(&["nat64", "192.0.2.33", "2001:db8:122:344::/96"]).unwrap(), "2001:db8:122:344::c000:221");
+    assert_eq!(run(&["6to4", "192.0.2.4"]).unwrap(), "2002:c000:204::/48");
+    // RFC 4380 section 4 example
+    assert_eq!(run(&["teredo", "65.54.227.120", "192.0.2.45", "40000", "0x8000"]).unwrap(), "2001:0:4136:e378:8000:63bf:3fff:fdd2");
+}
+
+#[test]
+fn command_transition_build_nok() {
+    let args: Vec<String> = ["nat64", "192.0.2.33", "2001:db8::/36"].iter().map(|s| s.to_string()).collect();
+    assert!(IpNetworkAddon.parse(&args).is_err());
+    let args: Vec<String> = ["mapped", "2001:db8::1"].iter().map(|s| s.to_string()).collect();
+    assert!(IpNetworkAddon.parse(&args).is_err());
+}
+
+// Tests for DecodeAddressCommand
+#[test]
+fn command_decode() {
+    let run = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        IpNetworkAddon.parse(&args).unwrap().execute()
+    };
+    assert_eq!(run(&["decode", "::ffff:10.1.2.3"]).unwrap(), "Type:   IPv4-mapped\nIPv4:   10.1.2.3");
+    assert_eq!(run(&["decode", "64:ff9b::c000:221"]).unwrap(), "Type:   NAT64 (64:ff9b::/96)\nIPv4:   192.0.2.33");
+    assert_eq!(run(&["decode", "2001:db8:122:344:c0:2:2100:0", "2001:db8:122:344::/64"]).unwrap(), "Type:   NAT64 (2001:db8:122:344::/64)\nIPv4:   192.0.2.33");
+    assert_eq!(run(&["decode", "2002:c000:204::1"]).unwrap(), "Type:   6to4\nIPv4:   192.0.2.4");
+    assert_eq!(run(&["decode", "2001:0:4136:e378:8000:63bf:3fff:fdd2"]).unwrap(), [
+        "Type:   Teredo",
+        "Server: 65.54.227.120",
+        "Client: 192.0.2.45",
+        "Port:   40000",
+        "Flags:  0x8000",
+    ].join("\n"));
+    assert!(run(&["decode", "2001:db8::1"]).is_err());
+}
+
+// Tests for Eui64AddressCommand, Eui64MacCommand and LinkLocalAddressCommand
+#[test]
+fn command_eui64() {
+    let run = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        IpNetworkAddon.parse(&args).unwrap().execute()
+    };
+    assert_eq!(run(&["eui64", "00:1a:2b:3c:4d:5e", "2001:db8:1:2::/64"]).unwrap(), "2001:db8:1:2:21a:2bff:fe3c:4d5e");
+    assert_eq!(run(&["eui64", "021A.2B3C.4D5E", "2001:db8:1:2::/64"]).unwrap(), "2001:db8:1:2:1a:2bff:fe3c:4d5e");
+    assert_eq!(run(&["eui64", "2001:db8:1:2:21a:2bff:fe3c:4d5e"]).unwrap(), "00:1a:2b:3c:4d:5e");
+    assert_eq!(run(&["link-local", "00-1a-2b-3c-4d-5e"]).unwrap(), "fe80::21a:2bff:fe3c:4d5e");
+    assert!(run(&["eui64", "2001:db8::1"]).is_err());
+}
+
+#[test]
+fn command_eui64_nok() {
+    let args: Vec<String> = ["eui64", "00:1a:2b:3c:4d:5e", "2001:db8::/48"].iter().map(|s| s.to_string()).collect();
+    assert!(IpNetworkAddon.parse(&args).is_err());
+    let args: Vec<String> = ["link-local", "00:1a:2b:3c:4d"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(IpNetworkAddon.parse(&args).err().unwrap(), MyToolsError::ParseCommandError("Invalid MAC address: '00:1a:2b:3c:4d'".to_string()));
+}
+
+// Tests for the structured results
+#[test]
+fn command_structured_results() {
+    let run = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        IpNetworkAddon.parse(&args).unwrap().execute().unwrap()
+    };
+    assert_eq!(run(&["get", "network", "10.1.2.3/8"]).value(), &CommandValue::object([("network", "10.0.0.0".into())]));
+    assert_eq!(run(&["summarize", "10.0.0.0/24", "10.0.1.0/24"]).render(OutputFormat::Csv), "network\n10.0.0.0/23");
+    assert_eq!(run(&["range", "10.0.0.0/30"]).render(OutputFormat::Yaml), "first: 10.0.0.0\nlast: 10.0.0.3");
+    assert_eq!(run(&["diff", "10.0.0.5", "10.0.0.1"]).render(OutputFormat::Json), "{\n  \"difference\": -4\n}");
+
+    let info = run(&["info", "2001:db8::/32"]);
+    match info.value() {
+        CommandValue::Object(fields) => {
+            assert!(fields.contains(&("broadcast".to_string(), CommandValue::Null)));
+            assert!(fields.contains(&("address_class".to_string(), CommandValue::Null)));
+            assert!(fields.contains(&("prefix_length".to_string(), CommandValue::Number("32".to_string()))));
+        },
+        value => panic!("Unexpected value: {:?}", value),
+    }
+}
+
+// Tests for the command specs
+#[test]
+fn command_spec_variants() {
+    let parse = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        IpNetworkAddon.parse(&args).and_then(|cmd| cmd.execute()).map(|result| result.to_string())
+    };
+    // A second argument is a prefix length or the end of a range
+    assert_eq!(parse(&["range", "10.0.0.0", "30"]).unwrap(), "10.0.0.0 - 10.0.0.3");
+    assert_eq!(parse(&["range", "10.0.0.0", "10.0.0.3"]).unwrap(), "10.0.0.0/30");
+    // A second argument shaped like a netmask is read as one
+    assert_eq!(parse(&["range", "10.0.0.0", "255.255.255.0"]).unwrap(), "10.0.0.0 - 10.0.0.255");
+    assert_eq!(parse(&["range", "0.0.0.0", "255.255.255.255"]).unwrap(), "0.0.0.0/0");
+    assert_eq!(parse(&["range", "2001:db8::", "ffff:ffff::"]).unwrap(), "2001:db8:: - 2001:db8:ffff:ffff:ffff:ffff:ffff:ffff");
+    assert!(parse(&["range", "10.0.0.0", "ffff:ffff::"]).is_err());
+    assert_eq!(parse(&["range", "10.0.0.0-10.0.0.3"]).unwrap(), "10.0.0.0/30");
+    assert_eq!(parse(&["range", "foo"]).unwrap_err(), MyToolsError::ParseCommandError("Argument 'foo' is neither a network nor a range".to_string()));
+    assert_eq!(parse(&["split", "10.0.0.0", "255.255.255.0", "--hosts", "100"]).unwrap(), "10.0.0.0/25\n10.0.0.128/25");
+    assert_eq!(parse(&["next", "10.0.0.0", "24", "2"]).unwrap(), "10.0.1.0/24\n10.0.2.0/24");
+    assert_eq!(parse(&["get", "adress", "10.0.0.1/24"]).unwrap_err(), MyToolsError::InvalidCommand("unknown command 'ipnet get adress' (did you mean 'ipnet get address'?)".to_string()));
+    assert_eq!(parse(&["hosts", "10.0.0.0/30", "--step", "0"]).unwrap_err(), MyToolsError::ParseCommandError("Invalid step: '0'".to_string()));
+}
+
+#[test]
+fn command_spec_help() {
+    let help = IpNetworkAddon.get_help();
+    assert!(help.contains("\t\tmy_tools ipnet hosts <ip> <cidr|mask> [--include-network-broadcast] [--limit N] [--offset N] [--step N] [--input <file>]\n"));
+    assert!(help.contains("\t\t    --used <ip/cidr,...|@file|-> Allocated networks, a comma separated list, '@<file>' or '-' for stdin\n"));
+    // Every command runs per line, except the list commands which read their whole input
+    assert!(help.contains("\t\t    --input <file>              Run for every line of a file ('-' as an argument reads stdin)\n"));
+    assert!(help.contains("\t\tmy_tools ipnet union [ip/cidr] ... [--input <file>]\n\t\t    --input <file>              Read more networks from a file\n"));
 }
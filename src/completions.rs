@@ -0,0 +1,290 @@
+use std::str::FromStr;
+
+use my_tools::{complete_command, MyToolsAddon, MyToolsError};
+
+/// Commands of my_tools itself, completed with the addon keywords
+const BUILTINS: &[&str] = &["shell", "completions"];
+/// Values of the global "--output" option
+const OUTPUT_FORMATS: &str = "text json yaml csv";
+
+/// Shells for which a completion script can be generated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+impl FromStr for Shell {
+    type Err = MyToolsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            "powershell" | "pwsh" => Ok(Shell::Powershell),
+            _ => Err(MyToolsError::ParseCommandError(format!("Invalid shell: '{}' (expected bash, zsh, fish or powershell)", s))),
+        }
+    }
+}
+
+/// Function to get the completions of every context, a context being the words of a command typed so far
+///
+/// Contexts are listed even without completions, so that the scripts know which words belong to a command
+fn contexts(addons: &[Box<dyn MyToolsAddon>]) -> Vec<(String, Vec<String>)> {
+    let mut root: Vec<String> = addons.iter().map(|addon| addon.get_keyword().to_string()).collect();
+    root.extend(BUILTINS.iter().map(|builtin| builtin.to_string()));
+    root.push("--output".to_string());
+
+    let mut contexts = vec![
+        (String::new(), root),
+        ("completions".to_string(), vec!["bash", "zsh", "fish", "powershell"].into_iter().map(String::from).collect()),
+        ("shell".to_string(), Vec::new()),
+    ];
+    for addon in addons {
+        let specs = addon.get_command_specs();
+        let mut paths: Vec<Vec<&str>> = vec![Vec::new()];
+        for spec in &specs {
+            for depth in 1..=spec.path.len() {
+                if !paths.iter().any(|path| path[..] == spec.path[..depth]) {
+                    paths.push(spec.path[..depth].to_vec());
+                }
+            }
+        }
+
+        // Next words of the commands, then their options
+        for path in paths {
+            let mut completions = complete_command(&specs, &path, "");
+            completions.extend(complete_command(&specs, &path, "-"));
+            let context = std::iter::once(addon.get_keyword()).chain(path).collect::<Vec<&str>>().join(" ");
+            contexts.push((context, completions));
+        }
+    }
+    contexts
+}
+
+/// Function to generate the completion script of a shell
+pub fn generate(shell: Shell, addons: &[Box<dyn MyToolsAddon>]) -> String {
+    let contexts = contexts(addons);
+    match shell {
+        Shell::Bash => bash_script(&contexts),
+        Shell::Zsh => zsh_script(&contexts),
+        Shell::Fish => fish_script(&contexts),
+        Shell::Powershell => powershell_script(&contexts),
+    }
+}
+
+/// Function to generate the end of the loop walking the words typed, and the choice of the completions,
+/// shared by bash and zsh
+fn posix_case(contexts: &[(String, Vec<String>)]) -> String {
+    let known = contexts
+        .iter()
+        .filter(|(context, _)| !context.is_empty())
+        .map(|(context, _)| format!("            \"{}\"", context))
+        .collect::<Vec<String>>()
+        .join(" | \\\n");
+    let completions = contexts
+        .iter()
+        .filter(|(_, completions)| !completions.is_empty())
+        .map(|(context, completions)| format!("        \"{}\") opts=\"{}\" ;;", context, completions.join(" ")))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!(r#"        case "${{context:+${{context}} }}${{word}}" in
+{known})
+                context="${{context:+${{context}} }}${{word}}" ;;
+        esac
+    done
+
+    case "${{context}}" in
+{completions}
+    esac
+"#,
+        known = known,
+        completions = completions
+    )
+}
+
+/// Function to generate the bash completion script
+fn bash_script(contexts: &[(String, Vec<String>)]) -> String {
+    format!(r#"# bash completion for my_tools, generated by 'my_tools completions bash'
+
+_my_tools() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}" prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    local context="" word opts=""
+
+    if [[ "${{prev}}" == "--output" ]]; then
+        COMPREPLY=($(compgen -W "{formats}" -- "${{cur}}"))
+        return
+    fi
+
+    # The context is made of the words of the command typed so far
+    for word in "${{COMP_WORDS[@]:1:COMP_CWORD-1}}"; do
+{case}    COMPREPLY=($(compgen -W "${{opts}}" -- "${{cur}}"))
+}}
+
+complete -F _my_tools my_tools
+"#,
+        formats = OUTPUT_FORMATS,
+        case = posix_case(contexts)
+    )
+}
+
+/// Function to generate the zsh completion script
+fn zsh_script(contexts: &[(String, Vec<String>)]) -> String {
+    format!(r#"#compdef my_tools
+# zsh completion for my_tools, generated by 'my_tools completions zsh'
+
+_my_tools() {{
+    local context="" word opts=""
+
+    if [[ "${{words[CURRENT-1]}}" == "--output" ]]; then
+        compadd -- {formats}
+        return
+    fi
+
+    # The context is made of the words of the command typed so far
+    for word in "${{(@)words[2,CURRENT-1]}}"; do
+{case}    compadd -- ${{=opts}}
+}}
+
+if [[ "${{funcstack[1]}}" == "_my_tools" ]]; then
+    _my_tools "$@"
+else
+    compdef _my_tools my_tools
+fi
+"#,
+        formats = OUTPUT_FORMATS,
+        case = posix_case(contexts)
+    )
+}
+
+/// Function to generate the fish completion script
+fn fish_script(contexts: &[(String, Vec<String>)]) -> String {
+    let known = contexts
+        .iter()
+        .filter(|(context, _)| !context.is_empty())
+        .map(|(context, _)| format!("'{}'", context))
+        .collect::<Vec<String>>()
+        .join(" ");
+    let completions = contexts
+        .iter()
+        .filter(|(_, completions)| !completions.is_empty())
+        .map(|(context, completions)| format!("complete -c my_tools -n \"test (__my_tools_context) = '{}'\" -a '{}'", context, completions.join(" ")))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!(r#"# fish completion for my_tools, generated by 'my_tools completions fish'
+
+# The context is made of the words of the command typed so far
+function __my_tools_context
+    set -l words (commandline -opc)
+    if test "$words[-1]" = '--output'
+        echo -- '--output'
+        return
+    end
+    set -l context ''
+    for word in $words[2..-1]
+        set -l next (string trim -- "$context $word")
+        if contains -- $next {known}
+            set context $next
+        end
+    end
+    echo $context
+end
+
+complete -c my_tools -f
+complete -c my_tools -n "test (__my_tools_context) = '--output'" -a '{formats}'
+{completions}
+"#,
+        known = known,
+        formats = OUTPUT_FORMATS,
+        completions = completions
+    )
+}
+
+/// Function to generate the PowerShell completion script
+fn powershell_script(contexts: &[(String, Vec<String>)]) -> String {
+    let known = contexts
+        .iter()
+        .filter(|(context, _)| !context.is_empty())
+        .map(|(context, _)| format!("        '{}'", context))
+        .collect::<Vec<String>>()
+        .join(",\n");
+    let completions = contexts
+        .iter()
+        .filter(|(_, completions)| !completions.is_empty())
+        .map(|(context, completions)| format!("        '{}' {{ @({}) }}", context, completions.iter().map(|completion| format!("'{}'", completion)).collect::<Vec<String>>().join(", ")))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!(r#"# PowerShell completion for my_tools, generated by 'my_tools completions powershell'
+
+Register-ArgumentCompleter -Native -CommandName my_tools -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $words = @($commandAst.CommandElements | Select-Object -Skip 1 | ForEach-Object {{ $_.ToString() }})
+    if ($wordToComplete) {{
+        $words = @($words | Select-Object -SkipLast 1)
+    }}
+
+    # The context is made of the words of the command typed so far
+    $contexts = @(
+{known}
+    )
+    $context = ''
+    foreach ($word in $words) {{
+        $next = "$context $word".Trim()
+        if ($contexts -contains $next) {{
+            $context = $next
+        }}
+    }}
+
+    $completions = switch ($context) {{
+{completions}
+        default {{ @() }}
+    }}
+    if ($words.Count -gt 0 -and $words[-1] -eq '--output') {{
+        $completions = @({formats})
+    }}
+
+    $completions | Where-Object {{ $_ -like "$wordToComplete*" }} | ForEach-Object {{
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }}
+}}
+"#,
+        known = known,
+        completions = completions,
+        formats = OUTPUT_FORMATS.split(' ').map(|format| format!("'{}'", format)).collect::<Vec<String>>().join(", ")
+    )
+}
+
+// Tests for the completion scripts, compared to the snapshots of src/snapshots
+// (run "UPDATE_SNAPSHOTS=1 cargo test" to update them after changing the commands of an addon)
+#[test]
+fn completion_snapshots() {
+    use crate::get_builtin_addons;
+
+    let addons = get_builtin_addons();
+    let snapshots = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join("snapshots");
+    for (shell, file) in [(Shell::Bash, "my_tools.bash"), (Shell::Zsh, "_my_tools"), (Shell::Fish, "my_tools.fish"), (Shell::Powershell, "my_tools.ps1")] {
+        let script = generate(shell, &addons);
+        let path = snapshots.join(file);
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::create_dir_all(&snapshots).unwrap();
+            std::fs::write(&path, &script).unwrap();
+            continue;
+        }
+        let snapshot = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(script == snapshot, "Completion script {} is out of date, run 'UPDATE_SNAPSHOTS=1 cargo test' and review the diff", file);
+    }
+}
+
+#[test]
+fn shell_from_str() {
+    assert_eq!("Bash".parse::<Shell>().unwrap(), Shell::Bash);
+    assert_eq!("pwsh".parse::<Shell>().unwrap(), Shell::Powershell);
+    assert!("tcsh".parse::<Shell>().is_err());
+}
@@ -1,15 +1,424 @@
-use std::{error, fmt};
+use std::{error, fmt, io};
+use std::any::Any;
+use std::io::BufRead;
+use std::str::FromStr;
 
-/// Type for the result of a command
-pub type CommandResult = String;
-/// Type for the input messages of a command
-pub type CommandInputs = Vec<String>;
-/// Type for the help message of a command
-pub type CommandHelp = String;
-/// Struct to group command inputs and help message
-pub struct CommandInputsHelp {
-    pub inputs_msg: CommandInputs,
-    pub help_msg: CommandHelp
+use serde::{Serialize, Serializer};
+
+pub mod plugin;
+
+/// Structured value of a command result, rendered in the output format chosen by the user
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandValue {
+    /// Missing value (e.g. no broadcast address)
+    Null,
+    Bool(bool),
+    /// Integer kept as its decimal digits, as IPv6 counts do not fit in any integer type
+    Number(String),
+    String(String),
+    List(Vec<CommandValue>),
+    /// Fields under stable keys, in display order
+    Object(Vec<(String, CommandValue)>),
+}
+
+impl CommandValue {
+    /// Function to build an object from its fields
+    pub fn object<'a>(fields: impl IntoIterator<Item = (&'a str, CommandValue)>) -> Self {
+        CommandValue::Object(fields
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect())
+    }
+
+    /// Function to get the default text form of the value
+    pub fn to_text(&self) -> String {
+        match self {
+            CommandValue::Null => String::new(),
+            CommandValue::Bool(value) => (if *value { "yes" } else { "no" }).to_string(),
+            CommandValue::Number(value) | CommandValue::String(value) => value.to_string(),
+            CommandValue::List(items) => items
+                .iter()
+                .map(|item| item.to_text())
+                .collect::<Vec<String>>()
+                .join("\n"),
+            // A single field is displayed alone, e.g. the address of {"address": "10.0.0.1"}
+            CommandValue::Object(fields) if fields.len() == 1 => fields[0].1.to_text(),
+            CommandValue::Object(fields) => fields
+                .iter()
+                .map(|(key, value)| format!("{}: {}", key, value.to_text()))
+                .collect::<Vec<String>>()
+                .join("\n"),
+        }
+    }
+
+    /// Function to get the JSON form of the value, indented by 2 spaces per level
+    fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.to_json_value()).unwrap_or_default()
+    }
+
+    /// Function to convert the value to JSON, numbers keeping all their digits even beyond 128 bits
+    fn to_json_value(&self) -> serde_json::Value {
+        match self {
+            CommandValue::Null => serde_json::Value::Null,
+            CommandValue::Bool(value) => serde_json::Value::Bool(*value),
+            CommandValue::Number(value) => value
+                .parse::<serde_json::Number>()
+                .map_or_else(|_| serde_json::Value::String(value.to_string()), serde_json::Value::Number),
+            CommandValue::String(value) => serde_json::Value::String(value.to_string()),
+            CommandValue::List(items) => serde_json::Value::Array(items.iter().map(CommandValue::to_json_value).collect()),
+            CommandValue::Object(fields) => serde_json::Value::Object(fields
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_json_value()))
+                .collect()),
+        }
+    }
+
+    /// Function to read a value from its JSON form (used by plugins to pass results)
+    fn from_json(json: &str) -> Option<CommandValue> {
+        serde_json::from_str(json).ok().map(CommandValue::from_json_value)
+    }
+
+    /// Function to convert a parsed JSON value, numbers keeping all their digits
+    fn from_json_value(value: serde_json::Value) -> CommandValue {
+        match value {
+            serde_json::Value::Null => CommandValue::Null,
+            serde_json::Value::Bool(value) => CommandValue::Bool(value),
+            serde_json::Value::Number(value) => CommandValue::Number(value.to_string()),
+            serde_json::Value::String(value) => CommandValue::String(value),
+            serde_json::Value::Array(items) => CommandValue::List(items.into_iter().map(CommandValue::from_json_value).collect()),
+            serde_json::Value::Object(fields) => CommandValue::Object(fields
+                .into_iter()
+                .map(|(key, value)| (key, CommandValue::from_json_value(value)))
+                .collect()),
+        }
+    }
+
+    /// Function to get the YAML form of the value
+    fn to_yaml(&self) -> String {
+        serde_norway::to_string(self).unwrap_or_default().trim_end().to_string()
+    }
+
+    /// Function to get the YAML form of the value as an item of a list
+    fn to_yaml_item(&self) -> String {
+        serde_norway::to_string(&[self]).unwrap_or_default().trim_end().to_string()
+    }
+
+    /// Function to get the CSV columns of a row, the keys of an object or a single "value" column
+    fn csv_columns(&self) -> Vec<String> {
+        match self {
+            CommandValue::Object(fields) => fields.iter().map(|(key, _)| key.to_string()).collect(),
+            _ => vec!["value".to_string()],
+        }
+    }
+
+    /// Function to get the CSV line of a row, with the given columns
+    fn to_csv_row(&self, columns: &[String]) -> String {
+        let cells: Vec<String> = match self {
+            CommandValue::Object(fields) => columns
+                .iter()
+                .map(|column| fields
+                    .iter()
+                    .find(|(key, _)| key == column)
+                    .map_or(String::new(), |(_, value)| value.to_csv_cell()))
+                .collect(),
+            _ => vec![self.to_csv_cell()],
+        };
+
+        csv_line(&cells)
+    }
+
+    /// Function to get the CSV cell of a value, nested lists and objects being joined with ";"
+    fn to_csv_cell(&self) -> String {
+        match self {
+            CommandValue::Null => String::new(),
+            CommandValue::Bool(value) => value.to_string(),
+            CommandValue::Number(value) | CommandValue::String(value) => value.to_string(),
+            CommandValue::List(items) => items
+                .iter()
+                .map(|item| item.to_csv_cell())
+                .collect::<Vec<String>>()
+                .join(";"),
+            CommandValue::Object(fields) => fields
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value.to_csv_cell()))
+                .collect::<Vec<String>>()
+                .join(";"),
+        }
+    }
+
+    /// Function to get the CSV form of the value: a header, then one line per item of a list (or a single line)
+    fn to_csv(&self) -> String {
+        let rows: Vec<&CommandValue> = match self {
+            CommandValue::List(items) => items.iter().collect(),
+            _ => vec![self],
+        };
+        // Columns are the ones of the first row, as every row of a result has the same keys
+        let columns = match rows.first() {
+            Some(row) => row.csv_columns(),
+            None => return String::new(),
+        };
+
+        std::iter::once(csv_line(&columns))
+            .chain(rows.iter().map(|row| row.to_csv_row(&columns)))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+impl From<bool> for CommandValue {
+    fn from(value: bool) -> Self {
+        CommandValue::Bool(value)
+    }
+}
+
+impl From<&str> for CommandValue {
+    fn from(value: &str) -> Self {
+        CommandValue::String(value.to_string())
+    }
+}
+
+impl From<String> for CommandValue {
+    fn from(value: String) -> Self {
+        CommandValue::String(value)
+    }
+}
+
+impl<T: Into<CommandValue>> From<Option<T>> for CommandValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(CommandValue::Null, |value| value.into())
+    }
+}
+
+impl<T: Into<CommandValue>> From<Vec<T>> for CommandValue {
+    fn from(value: Vec<T>) -> Self {
+        CommandValue::List(value.into_iter().map(|item| item.into()).collect())
+    }
+}
+
+/// Implement From for the integer types, kept as their decimal digits
+macro_rules! impl_from_integer {
+    ($($integer:ty),*) => {
+        $(impl From<$integer> for CommandValue {
+            fn from(value: $integer) -> Self {
+                CommandValue::Number(value.to_string())
+            }
+        })*
+    };
+}
+
+impl_from_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+// Numbers beyond the 128-bit integers (e.g. the difference between the ends of the IPv6 space) are written as strings
+impl Serialize for CommandValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            CommandValue::Null => serializer.serialize_unit(),
+            CommandValue::Bool(value) => serializer.serialize_bool(*value),
+            CommandValue::Number(value) => match (value.parse::<u128>(), value.parse::<i128>()) {
+                (Ok(value), _) => serializer.serialize_u128(value),
+                (_, Ok(value)) => serializer.serialize_i128(value),
+                _ => serializer.serialize_str(value),
+            },
+            CommandValue::String(value) => serializer.serialize_str(value),
+            CommandValue::List(items) => serializer.collect_seq(items),
+            CommandValue::Object(fields) => serializer.collect_map(fields.iter().map(|(key, value)| (key, value))),
+        }
+    }
+}
+
+/// Function to write the fields of a CSV line, quoted when needed (RFC 4180)
+fn csv_line(fields: &[String]) -> String {
+    let mut writer = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::Any(b'\n'))
+        .from_writer(Vec::new());
+    let line = writer.write_record(fields)
+        .ok()
+        .and_then(|_| writer.into_inner().ok())
+        .unwrap_or_default();
+    String::from_utf8_lossy(&line).trim_end_matches('\n').to_string()
+}
+
+/// Result of a command: a structured value, and the text displayed by default
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandResult {
+    value: CommandValue,
+    text: String,
+}
+
+impl CommandResult {
+    /// Function to build a result displayed as the default text form of its value
+    pub fn new(value: CommandValue) -> Self {
+        let text = value.to_text();
+        CommandResult { value, text }
+    }
+
+    /// Function to build a result with its own text form (e.g. a table or aligned labels)
+    pub fn with_text(value: CommandValue, text: impl Into<String>) -> Self {
+        CommandResult { value, text: text.into() }
+    }
+
+    /// Function to build a result made of a single field, displayed alone
+    pub fn field(key: &str, value: impl Into<CommandValue>) -> Self {
+        CommandResult::new(CommandValue::object([(key, value.into())]))
+    }
+
+    /// Function to get the structured value of the result
+    pub fn value(&self) -> &CommandValue {
+        &self.value
+    }
+
+    /// Function to get the text form of the result
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Function to render the result in an output format
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Text => self.text.to_string(),
+            OutputFormat::Json => self.value.to_json(),
+            OutputFormat::Yaml => self.value.to_yaml(),
+            OutputFormat::Csv => self.value.to_csv(),
+        }
+    }
+}
+
+impl fmt::Display for CommandResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+// A result is equal to a string if its text form is that string
+impl PartialEq<str> for CommandResult {
+    fn eq(&self, other: &str) -> bool {
+        self.text == other
+    }
+}
+
+impl PartialEq<&str> for CommandResult {
+    fn eq(&self, other: &&str) -> bool {
+        self.text == *other
+    }
+}
+
+impl PartialEq<String> for CommandResult {
+    fn eq(&self, other: &String) -> bool {
+        &self.text == other
+    }
+}
+
+/// Output formats of the command results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Text for humans (default)
+    #[default]
+    Text,
+    Json,
+    Yaml,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = MyToolsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "yaml" => Ok(OutputFormat::Yaml),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(MyToolsError::ParseCommandError(format!("Invalid output format: '{}' (expected text, json, yaml or csv)", s))),
+        }
+    }
+}
+
+/// Writer of the command results, in the output format chosen by the user
+///
+/// Large results are streamed item by item between `begin_list` and `end_list`, so they never need to fit in memory
+pub struct ResultWriter<'a> {
+    output: &'a mut dyn io::Write,
+    format: OutputFormat,
+    /// Depth of the open lists, nested lists are flattened into the outer one
+    lists: usize,
+    /// Number of items written in the open list
+    items: usize,
+    /// CSV columns of the open list, the ones of its first item
+    columns: Vec<String>,
+}
+
+impl<'a> ResultWriter<'a> {
+    /// Function to create a writer of results to an output
+    pub fn new(output: &'a mut dyn io::Write, format: OutputFormat) -> Self {
+        ResultWriter { output, format, lists: 0, items: 0, columns: Vec::new() }
+    }
+
+    /// Function to get the output format
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
+    /// Function to write a whole result, or an item if a list is open
+    ///
+    /// Returns false if the output has been closed (e.g. piped to `head`), so the command can stop quietly
+    pub fn write_result(&mut self, result: &CommandResult) -> Result<bool, MyToolsError> {
+        if self.lists > 0 {
+            return self.write_item(result)
+        }
+        let rendered = result.render(self.format);
+        write_result_str(self.output, &format!("{}\n", rendered))
+    }
+
+    /// Function to open a streamed list of results
+    pub fn begin_list(&mut self) -> Result<bool, MyToolsError> {
+        self.lists += 1;
+        if self.lists == 1 {
+            self.items = 0;
+            self.columns.clear();
+        }
+        Ok(true)
+    }
+
+    /// Function to write an item of the open list
+    pub fn write_item(&mut self, item: &CommandResult) -> Result<bool, MyToolsError> {
+        let rendered = match self.format {
+            OutputFormat::Text => format!("{}\n", item.text),
+            // The list is opened with its first item, indented as an item, and closed by end_list
+            OutputFormat::Json => format!("{}\n  {}", if self.items == 0 { "[" } else { "," }, item.value.to_json().replace('\n', "\n  ")),
+            OutputFormat::Yaml => format!("{}\n", item.value.to_yaml_item()),
+            OutputFormat::Csv if self.items == 0 => {
+                self.columns = item.value.csv_columns();
+                format!("{}\n{}\n", csv_line(&self.columns), item.value.to_csv_row(&self.columns))
+            },
+            OutputFormat::Csv => format!("{}\n", item.value.to_csv_row(&self.columns)),
+        };
+        self.items += 1;
+        write_result_str(self.output, &rendered)
+    }
+
+    /// Function to close the open list
+    pub fn end_list(&mut self) -> Result<bool, MyToolsError> {
+        self.lists = self.lists.saturating_sub(1);
+        if self.lists > 0 {
+            return Ok(true)
+        }
+        match (self.format, self.items) {
+            (OutputFormat::Json, 0) | (OutputFormat::Yaml, 0) => write_result_str(self.output, "[]\n"),
+            (OutputFormat::Json, _) => write_result_str(self.output, "\n]\n"),
+            _ => Ok(true),
+        }
+    }
+}
+
+/// Function to write a part of a command result
+///
+/// Returns false if the output has been closed (e.g. piped to `head`), so the command can stop quietly
+fn write_result_str(output: &mut dyn io::Write, value: &str) -> Result<bool, MyToolsError> {
+    match output.write_all(value.as_bytes()) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(false),
+        Err(e) => Err(MyToolsError::ExecutionCommandError(format!("Cannot write result: {}", e))),
+    }
 }
 
 /// Trait designed to be implemented by every addon commands
@@ -17,43 +426,770 @@ pub trait MyToolsAddonCommand {
     /// Function to execute the command
     fn execute(&self) -> Result<CommandResult, MyToolsError>;
 
-    /// Function to get the input of the command
-    fn get_command_input() -> CommandInputs where Self: Sized;
+    /// Function to execute the command and write its result to an output
+    ///
+    /// Commands producing large results should override it to stream their output item by item.
+    /// A negative answer is written like any result before it is returned
+    fn execute_to(&self, output: &mut ResultWriter) -> Result<(), MyToolsError> {
+        match self.execute() {
+            Err(MyToolsError::NegativeResult(result)) => {
+                output.write_result(&result)?;
+                Err(MyToolsError::NegativeResult(result))
+            },
+            result => {
+                output.write_result(&result?)?;
+                Ok(())
+            },
+        }
+    }
 
-    /// Function to get the help message of the command
-    fn get_command_help() -> CommandHelp where Self: Sized;
+    /// Function to get the specs of the command, one per variant
+    fn get_command_specs() -> Vec<CommandSpec> where Self: Sized;
 }
 
-/// Trait designed to be implemented by every addons
-pub trait MyToolsAddon {
-    /// Function to get the help message of the addon
-    fn get_help(&self) -> String {
-        // Get addon's keyword
-        let keyword: &str = self.get_keyword();
+/// Type of the value of an argument or an option, checked before a command variant is chosen
+#[derive(Clone, Copy)]
+pub enum ValueType {
+    /// Any value
+    Text,
+    /// Non-negative integer
+    Unsigned,
+    /// Integer, possibly negative
+    Integer,
+    /// Value parsed by a function of the addon, returning the reason why it is invalid
+    Custom(fn(&str) -> Result<ParsedValue, MyToolsError>),
+}
 
-        // Get the list of commands
-        let command_list: Vec<CommandInputsHelp> = self.get_list_commands();
+/// Value parsed by a custom value type, given to the builder of the command through ParsedArgs::parsed
+pub type ParsedValue = Box<dyn Any>;
+
+/// Function to wrap the result of the parser of a custom value type
+pub fn parsed_value<T: Any>(value: T) -> ParsedValue {
+    Box::new(value)
+}
+
+impl ValueType {
+    /// Function to check a value, the name of its argument being used in the error message
+    fn check(&self, name: &str, value: &str) -> Result<Option<ParsedValue>, MyToolsError> {
+        let is_digits = |digits: &str| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit());
+        let is_valid = match self {
+            ValueType::Text => true,
+            ValueType::Unsigned => is_digits(value),
+            ValueType::Integer => is_digits(value.strip_prefix('-').unwrap_or(value)),
+            ValueType::Custom(parse) => return parse(value).map(Some),
+        };
+
+        match is_valid {
+            true => Ok(None),
+            false => Err(MyToolsError::ParseCommandError(format!("Invalid {}: '{}'", name, value))),
+        }
+    }
+}
+
+/// Number of values taken by a positional argument
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arity {
+    One,
+    Optional,
+    /// Every remaining value, at least one (last argument only)
+    OneOrMore,
+    /// Every remaining value (last argument only)
+    ZeroOrMore,
+}
+
+/// Specification of a positional argument
+#[derive(Clone)]
+pub struct ArgSpec {
+    /// Name used to get the value, and displayed in the usage
+    pub name: &'static str,
+    pub arity: Arity,
+    pub value_type: ValueType,
+    /// "-" reads the values of the argument from stdin, see `stdin`
+    pub stdin: bool,
+}
+
+impl ArgSpec {
+    /// Function to create a required argument
+    pub fn required(name: &'static str, value_type: ValueType) -> Self {
+        ArgSpec { name, arity: Arity::One, value_type, stdin: false }
+    }
+
+    /// Function to create an optional argument
+    pub fn optional(name: &'static str, value_type: ValueType) -> Self {
+        ArgSpec { name, arity: Arity::Optional, value_type, stdin: false }
+    }
+
+    /// Function to create an argument taking every remaining value, at least one
+    pub fn one_or_more(name: &'static str, value_type: ValueType) -> Self {
+        ArgSpec { name, arity: Arity::OneOrMore, value_type, stdin: false }
+    }
+
+    /// Function to create an argument taking every remaining value
+    pub fn zero_or_more(name: &'static str, value_type: ValueType) -> Self {
+        ArgSpec { name, arity: Arity::ZeroOrMore, value_type, stdin: false }
+    }
+
+    /// Function to let "-" as a value read the values of the argument from stdin
+    pub fn stdin(mut self) -> Self {
+        self.stdin = true;
+        self
+    }
+
+    /// Function to get the usage of the argument (e.g. "<ip/cidr>" or "[count]")
+    pub fn usage(&self) -> String {
+        match self.arity {
+            Arity::One => format!("<{}>", self.name),
+            Arity::Optional => format!("[{}]", self.name),
+            Arity::OneOrMore => format!("<{}> ...", self.name),
+            Arity::ZeroOrMore => format!("[{}] ...", self.name),
+        }
+    }
+}
+
+/// Specification of an option, a flag ("--name") or an option with a value ("--name <value>" or "--name=<value>")
+#[derive(Clone)]
+pub struct OptionSpec {
+    /// Name of the option, without the leading "--"
+    pub name: &'static str,
+    /// Name of the value displayed in the usage, None for a flag
+    pub value_name: Option<&'static str>,
+    pub value_type: ValueType,
+    pub required: bool,
+    pub help: &'static str,
+    /// "-" as a value reads the values of the option from stdin, see `stdin`
+    pub stdin: bool,
+}
+
+impl OptionSpec {
+    /// Function to create a flag
+    pub fn flag(name: &'static str, help: &'static str) -> Self {
+        OptionSpec { name, value_name: None, value_type: ValueType::Text, required: false, help, stdin: false }
+    }
+
+    /// Function to create an option taking a value
+    pub fn value(name: &'static str, value_name: &'static str, value_type: ValueType, help: &'static str) -> Self {
+        OptionSpec { name, value_name: Some(value_name), value_type, required: false, help, stdin: false }
+    }
+
+    /// Function to make the option required
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Function to let "-" as a value read the values of the option from stdin
+    pub fn stdin(mut self) -> Self {
+        self.stdin = true;
+        self
+    }
+
+    /// Function to get the usage of the option (e.g. "[--limit N]")
+    pub fn usage(&self) -> String {
+        let usage = match self.value_name {
+            Some(value_name) => format!("--{} {}", self.name, value_name),
+            None => format!("--{}", self.name),
+        };
+        match self.required {
+            true => usage,
+            false => format!("[{}]", usage),
+        }
+    }
+}
+
+/// Function building a command from its parsed arguments
+pub type CommandBuilder = fn(&ParsedArgs) -> Result<Box<dyn MyToolsAddonCommand>, MyToolsError>;
+
+/// Specification of a command: its words, arguments and options, its help message and how to build it
+///
+/// Specs sharing the same words are variants of a command (e.g. "<ip/cidr>" and "<ip> <mask>"),
+/// the first variant whose arguments are valid is built
+#[derive(Clone)]
+pub struct CommandSpec {
+    /// Words selecting the command (e.g. ["get", "address"])
+    pub path: Vec<&'static str>,
+    pub help: String,
+    pub args: Vec<ArgSpec>,
+    pub options: Vec<OptionSpec>,
+    /// Run once per line of stdin or of a file, see `lines`
+    pub lines: bool,
+    pub build: CommandBuilder,
+}
+
+/// Reason why the arguments do not match a command variant
+enum Mismatch {
+    /// The arguments are not shaped like the variant (number of arguments, unknown or missing options),
+    /// with an error worth reporting if no other variant matches better
+    Shape(Option<MyToolsError>),
+    /// The arguments are shaped like the variant but a value is invalid
+    Invalid(MyToolsError),
+}
 
-        // Get the commands input and help message
-        let commands_text_message: Vec<String> = command_list.iter().map(|cmd| {
-            // Generate list of command inputs
-            let command_inputs = cmd.inputs_msg
+impl CommandSpec {
+    /// Function to create the spec of a command, its words being separated by spaces (e.g. "get address")
+    pub fn new(path: &'static str, help: impl Into<String>, build: CommandBuilder) -> Self {
+        CommandSpec { path: path.split_whitespace().collect(), help: help.into(), args: Vec::new(), options: Vec::new(), lines: false, build }
+    }
+
+    /// Function to let the command run once per line of stdin ("-" as an argument) or of a file ("--input <file>")
+    ///
+    /// The words of each line take the place of "-", or follow the arguments if there is none
+    pub fn lines(mut self) -> Self {
+        self.lines = true;
+        self.option(OptionSpec::value("input", "<file>", ValueType::Text, "Run for every line of a file ('-' as an argument reads stdin)"))
+    }
+
+    /// Function to add a positional argument
+    pub fn arg(mut self, arg: ArgSpec) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    /// Function to add an option
+    pub fn option(mut self, option: OptionSpec) -> Self {
+        self.options.push(option);
+        self
+    }
+
+    /// Function to get the usage of the command (e.g. "hosts <ip/cidr> [--limit N]")
+    pub fn usage(&self) -> String {
+        self.path.iter()
+            .map(|word| word.to_string())
+            .chain(self.args.iter().map(|arg| arg.usage()))
+            .chain(self.options.iter().map(|option| option.usage()))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Function to check if the arguments start with the words of the command
+    fn matches<S: AsRef<str>>(&self, args: &[S]) -> bool {
+        self.path.len() <= args.len() && self.path.iter().zip(args).all(|(word, arg)| *word == arg.as_ref())
+    }
+
+    /// Function to check if the arguments following the words of the command read stdin,
+    /// "-" being given to a command run per line, or to an argument or an option reading its values from stdin
+    fn reads_stdin(&self, args: &[String]) -> bool {
+        let mut dash = false;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                dash |= args.by_ref().any(|arg| arg == "-");
+                break;
+            }
+            let option = match arg.strip_prefix("--") {
+                Some(option) => option,
+                None => {
+                    dash |= arg == "-";
+                    continue;
+                },
+            };
+
+            let (name, inline_value) = match option.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (option, None),
+            };
+            // The variant does not match unknown options
+            let spec = match self.options.iter().find(|spec| spec.name == name) {
+                Some(spec) => spec,
+                None => return false,
+            };
+            if spec.value_name.is_some() {
+                let value = inline_value.or_else(|| args.next().map(|value| value.as_str()));
+                if spec.stdin && value == Some("-") {
+                    return true
+                }
+            }
+        }
+        dash && (self.lines || self.args.iter().any(|arg| arg.stdin))
+    }
+
+    /// Function to parse the arguments following the words of the command
+    fn parse_args(&self, args: &[String]) -> Result<ParsedArgs, Mismatch> {
+        let mut parsed = ParsedArgs::default();
+        let mut positionals: Vec<&str> = Vec::new();
+
+        // Options can be anywhere, until "--" after which every argument is positional
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                positionals.extend(args.by_ref().map(|arg| arg.as_str()));
+                break;
+            }
+            let option = match arg.strip_prefix("--") {
+                Some(option) => option,
+                None => {
+                    positionals.push(arg);
+                    continue;
+                },
+            };
+
+            let (name, inline_value) = match option.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (option, None),
+            };
+            let spec = self.options
                 .iter()
-                .map(|input| {
-                    format!("\t\tmy_tools {} {}", keyword, input)
-                })
-                .collect::<Vec<String>>();
-
-            // Generate the whole command message
-            format!(
-                "\t{}\n{}\n",
-                cmd.help_msg,
-                command_inputs.join("\n")
-            )
-        }).collect::<Vec<String>>();
-
-        // Generate the help message
-        format!(r#"
+                .find(|spec| spec.name == name)
+                .ok_or_else(|| {
+                    let names: Vec<&str> = self.options.iter().map(|spec| spec.name).collect();
+                    let suggestion = closest_word(name, &names)
+                        .map_or(String::new(), |name| format!(" (did you mean '--{}'?)", name));
+                    Mismatch::Shape(Some(MyToolsError::ParseCommandError(format!("Unknown option '--{}'{}", name, suggestion))))
+                })?;
+
+            let value = match (spec.value_name, inline_value) {
+                (None, None) => String::new(),
+                (None, Some(_)) => return Err(Mismatch::Invalid(MyToolsError::ParseCommandError(format!("Option '--{}' does not take a value", name)))),
+                (Some(_), Some(value)) => value.to_string(),
+                (Some(_), None) => args.next()
+                    .ok_or(Mismatch::Invalid(MyToolsError::ParseCommandError(format!("Missing value for option '--{}'", name))))?
+                    .to_string(),
+            };
+            if spec.value_name.is_some() {
+                parsed.push(spec.name, &value, spec.value_type.check(spec.name, &value).map_err(Mismatch::Invalid)?);
+            } else {
+                parsed.push(spec.name, &value, None);
+            }
+        }
+
+        if self.options.iter().any(|spec| spec.required && parsed.value(spec.name).is_none()) {
+            return Err(Mismatch::Shape(None))
+        }
+
+        // Optional arguments take the values left by the required ones, from left to right
+        let min = self.args.iter().filter(|arg| matches!(arg.arity, Arity::One | Arity::OneOrMore)).count();
+        let is_unbounded = self.args.iter().any(|arg| matches!(arg.arity, Arity::OneOrMore | Arity::ZeroOrMore));
+        let max = min + self.args.iter().filter(|arg| arg.arity == Arity::Optional).count();
+        if positionals.len() < min || (!is_unbounded && positionals.len() > max) {
+            return Err(Mismatch::Shape(None))
+        }
+
+        let mut extra = positionals.len() - min;
+        let mut positionals = positionals.into_iter();
+        for arg in &self.args {
+            let count = match arg.arity {
+                Arity::One => 1,
+                Arity::Optional if extra > 0 => {
+                    extra -= 1;
+                    1
+                },
+                Arity::Optional => 0,
+                Arity::OneOrMore => 1 + std::mem::take(&mut extra),
+                Arity::ZeroOrMore => std::mem::take(&mut extra),
+            };
+            for value in positionals.by_ref().take(count) {
+                parsed.push(arg.name, value, arg.value_type.check(arg.name, value).map_err(Mismatch::Invalid)?);
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// Arguments and options of a command, parsed from its spec
+#[derive(Debug, Default)]
+pub struct ParsedArgs {
+    /// Values by argument or option name, in the order they were given
+    values: Vec<(String, String)>,
+    /// Values parsed by custom value types, by argument or option name
+    parsed: Vec<(String, ParsedValue)>,
+}
+
+impl ParsedArgs {
+    /// Function to add a value and, for a custom value type, its parsed value
+    fn push(&mut self, name: &str, value: &str, parsed: Option<ParsedValue>) {
+        self.values.push((name.to_string(), value.to_string()));
+        if let Some(parsed) = parsed {
+            self.parsed.push((name.to_string(), parsed));
+        }
+    }
+
+    /// Function to get the value of an argument or an option (the last one if given several times)
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values(name).pop()
+    }
+
+    /// Function to get every value of an argument or an option
+    pub fn values(&self, name: &str) -> Vec<&str> {
+        self.values
+            .iter()
+            .filter(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    /// Function to get the value of a required argument, which its spec guarantees to be present
+    pub fn arg(&self, name: &str) -> &str {
+        self.value(name).unwrap_or_default()
+    }
+
+    /// Function to check if a flag is set
+    pub fn flag(&self, name: &str) -> bool {
+        self.value(name).is_some()
+    }
+
+    /// Function to get the value of an argument or an option as parsed by its custom value type (the last one if given several times)
+    pub fn parsed<T: Any + Clone>(&self, name: &str) -> Option<T> {
+        self.parsed_values(name).pop()
+    }
+
+    /// Function to get every value of an argument or an option as parsed by its custom value type
+    pub fn parsed_values<T: Any + Clone>(&self, name: &str) -> Vec<T> {
+        self.parsed
+            .iter()
+            .filter(|(key, _)| key == name)
+            .filter_map(|(_, value)| value.downcast_ref::<T>().cloned())
+            .collect()
+    }
+
+    /// Function to get the parsed value of a required argument, an error meaning that its spec has another value type
+    pub fn parsed_arg<T: Any + Clone>(&self, name: &str) -> Result<T, MyToolsError> {
+        self.parsed(name)
+            .ok_or_else(|| MyToolsError::ParseCommandError(format!("Missing parsed value for '{}'", name)))
+    }
+
+    /// Function to get the value of an argument or an option as a number
+    pub fn number<T: FromStr>(&self, name: &str) -> Result<Option<T>, MyToolsError> {
+        self.value(name)
+            .map(|value| value.parse::<T>().map_err(|_| MyToolsError::ParseCommandError(format!("Invalid {}: '{}'", name, value))))
+            .transpose()
+    }
+}
+
+/// Function to check if the arguments of an addon read stdin, with the specs of its commands
+pub fn reads_stdin(specs: &[CommandSpec], args: &[String]) -> bool {
+    let depth = specs.iter().filter(|spec| spec.matches(args)).map(|spec| spec.path.len()).max();
+    specs
+        .iter()
+        .filter(|spec| Some(spec.path.len()) == depth && spec.matches(args))
+        .any(|spec| spec.reads_stdin(&args[spec.path.len()..]))
+}
+
+/// Function to parse the arguments of an addon with the specs of its commands
+pub fn parse_command(keyword: &str, specs: &[CommandSpec], args: &[String]) -> Result<Box<dyn MyToolsAddonCommand>, MyToolsError> {
+    // Variants of the command with the most words matching the arguments
+    let depth = match specs.iter().filter(|spec| spec.matches(args)).map(|spec| spec.path.len()).max() {
+        Some(depth) => depth,
+        None => return Err(unknown_command(keyword, specs, args)),
+    };
+    let variants: Vec<&CommandSpec> = specs
+        .iter()
+        .filter(|spec| spec.path.len() == depth && spec.matches(args))
+        .collect();
+
+    // "-" or "--input" run the command once per line instead
+    if let Some(command) = LinesCommand::parse(keyword, &variants, args, depth)? {
+        return Ok(Box::new(command))
+    }
+
+    // The error of the first variant shaped like the arguments is the most relevant one
+    let (mut invalid, mut shape): (Option<MyToolsError>, Option<MyToolsError>) = (None, None);
+    for variant in &variants {
+        match variant.parse_args(&args[depth..]) {
+            Ok(parsed) => return (variant.build)(&parsed),
+            Err(Mismatch::Invalid(e)) => { invalid.get_or_insert(e); },
+            Err(Mismatch::Shape(e)) => { shape = shape.or(e); },
+        }
+    }
+
+    Err(invalid.or(shape).unwrap_or_else(|| {
+        let command = std::iter::once(keyword).chain(variants[0].path.iter().copied()).collect::<Vec<&str>>().join(" ");
+        let usages = variants
+            .iter()
+            .map(|variant| format!("\tmy_tools {} {}", keyword, variant.usage()).trim_end().to_string())
+            .collect::<Vec<String>>();
+        MyToolsError::InvalidCommand(format!("wrong arguments for '{}', expected:\n{}", command, usages.join("\n")))
+    }))
+}
+
+/// Command run once per line of stdin or of a file, the variants of a command being parsed again for every line
+struct LinesCommand {
+    keyword: String,
+    /// Variants of the command, which do not run per line themselves
+    variants: Vec<CommandSpec>,
+    /// Words and arguments of the command, where "-" is replaced by the words of each line
+    args: Vec<String>,
+    /// File to read, stdin if None
+    input: Option<String>,
+}
+
+impl LinesCommand {
+    /// Function to get the command run per line, if the arguments have "-" as an argument or the "--input" option
+    fn parse(keyword: &str, variants: &[&CommandSpec], args: &[String], depth: usize) -> Result<Option<LinesCommand>, MyToolsError> {
+        let variants: Vec<CommandSpec> = variants
+            .iter()
+            .filter(|variant| variant.lines)
+            .map(|variant| {
+                let mut variant = (*variant).clone();
+                variant.lines = false;
+                variant.options.retain(|option| option.name != "input");
+                variant
+            })
+            .collect();
+        if variants.is_empty() {
+            return Ok(None)
+        }
+
+        // The value of an option is never "-" as an argument
+        let takes_value = |name: &str| variants
+            .iter()
+            .flat_map(|variant| &variant.options)
+            .any(|option| option.name == name && option.value_name.is_some());
+        let (mut kept, mut input, mut stdin) = (args[..depth].to_vec(), None, false);
+        let mut rest = args[depth..].iter();
+        while let Some(arg) = rest.next() {
+            match arg.as_str() {
+                "-" => stdin = true,
+                "--" => {
+                    kept.push(arg.to_string());
+                    for arg in rest.by_ref() {
+                        stdin |= arg == "-";
+                        kept.push(arg.to_string());
+                    }
+                    break;
+                },
+                "--input" => {
+                    let value = rest.next().ok_or(MyToolsError::ParseCommandError("Missing value for option '--input'".to_string()))?;
+                    input = Some(value.to_string());
+                    continue;
+                },
+                _ if arg.starts_with("--input=") => {
+                    input = arg.strip_prefix("--input=").map(|value| value.to_string());
+                    continue;
+                },
+                _ if arg.strip_prefix("--").is_some_and(takes_value) => {
+                    kept.push(arg.to_string());
+                    kept.extend(rest.next().cloned());
+                    continue;
+                },
+                _ => {},
+            }
+            kept.push(arg.to_string());
+        }
+
+        match stdin || input.is_some() {
+            true => Ok(Some(LinesCommand { keyword: keyword.to_string(), variants, args: kept, input })),
+            false => Ok(None),
+        }
+    }
+
+    /// Function to parse the command of every line and run it, until `run` returns false
+    ///
+    /// A line which cannot be parsed or run is given to `run` as an error item, and the run ends with a negative result
+    fn run_lines(&self, mut run: impl FnMut(Result<Box<dyn MyToolsAddonCommand>, CommandResult>) -> Result<bool, MyToolsError>) -> Result<(), MyToolsError> {
+        let reader: Box<dyn BufRead> = match &self.input {
+            Some(input) => {
+                let file = std::fs::File::open(input)
+                    .map_err(|e| MyToolsError::ParseCommandError(format!("Cannot read '{}': {}", input, e)))?;
+                Box::new(io::BufReader::new(file))
+            },
+            None => Box::new(io::stdin().lock()),
+        };
+
+        // Errors are reported with their line number, without stopping the other lines
+        let (mut lines, mut errors) = (0, 0);
+        for (index, line) in reader.lines().enumerate() {
+            let line = line
+                .map_err(|e| MyToolsError::ParseCommandError(format!("Cannot read line {}: {}", index + 1, e)))?;
+            let words: Vec<String> = line.split_whitespace().map(|word| word.to_string()).collect();
+            if words.is_empty() || words[0].starts_with('#') {
+                continue;
+            }
+            lines += 1;
+
+            let mut args: Vec<String> = Vec::new();
+            for arg in &self.args {
+                match arg.as_str() {
+                    "-" => args.extend(words.iter().cloned()),
+                    _ => args.push(arg.to_string()),
+                }
+            }
+            if !self.args.iter().any(|arg| arg == "-") {
+                args.extend(words);
+            }
+
+            let result = match parse_command(&self.keyword, &self.variants, &args).and_then(|command| run(Ok(command))) {
+                Err(e) => {
+                    errors += 1;
+                    let item = CommandValue::object([
+                        ("line", (index + 1).into()),
+                        ("input", line.as_str().into()),
+                        ("error", e.to_string().into()),
+                    ]);
+                    run(Err(CommandResult::with_text(item, format!("line {}: {}", index + 1, e))))?
+                },
+                result => result?,
+            };
+            if !result {
+                break;
+            }
+        }
+
+        if errors > 0 {
+            let summary = CommandValue::object([("lines", lines.into()), ("failed", errors.into())]);
+            return Err(MyToolsError::NegativeResult(CommandResult::with_text(summary, format!("{} of {} lines failed", errors, lines))))
+        }
+        Ok(())
+    }
+}
+
+impl MyToolsAddonCommand for LinesCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let mut results: Vec<CommandResult> = Vec::new();
+        let status = self.run_lines(|command| {
+            // A negative answer is still the result of its line
+            results.push(match command.map(|command| command.execute()) {
+                Ok(Err(MyToolsError::NegativeResult(result))) | Err(result) => result,
+                Ok(result) => result?,
+            });
+            Ok(true)
+        });
+
+        let text = results.iter().map(|result| result.text()).collect::<Vec<&str>>().join("\n");
+        let result = CommandResult::with_text(CommandValue::List(results.into_iter().map(|result| result.value).collect()), text);
+        match status {
+            // The failed lines are part of the result
+            Err(MyToolsError::NegativeResult(_)) => Err(MyToolsError::NegativeResult(result)),
+            status => status.map(|_| result),
+        }
+    }
+
+    fn execute_to(&self, output: &mut ResultWriter) -> Result<(), MyToolsError> {
+        // Results and failed lines are streamed as the items of a single list, line by line
+        output.begin_list()?;
+        let result = self.run_lines(|command| match command {
+            Ok(command) => match command.execute_to(output) {
+                // A negative answer is already written as the result of its line
+                Ok(()) | Err(MyToolsError::NegativeResult(_)) => Ok(true),
+                Err(e) => Err(e),
+            },
+            Err(item) => output.write_result(&item),
+        });
+        output.end_list()?;
+        result
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        // Runs per line are declared by the specs of the commands, see CommandSpec::lines
+        Vec::new()
+    }
+}
+
+/// Function to get the error for arguments matching no command, suggesting the closest one
+fn unknown_command(keyword: &str, specs: &[CommandSpec], args: &[String]) -> MyToolsError {
+    // Number of words matching the beginning of at least one command
+    let is_prefix = |spec: &CommandSpec, depth: usize| spec.path.len() > depth && spec.path.iter().zip(&args[..depth]).all(|(word, arg)| word == arg);
+    let mut depth = 0;
+    while depth < args.len() && specs.iter().any(|spec| is_prefix(spec, depth) && spec.path[depth] == args[depth]) {
+        depth += 1;
+    }
+
+    let mut words: Vec<&str> = Vec::new();
+    for spec in specs.iter().filter(|spec| is_prefix(spec, depth)) {
+        if !words.contains(&spec.path[depth]) {
+            words.push(spec.path[depth]);
+        }
+    }
+
+    let command = std::iter::once(keyword).chain(args[..depth].iter().map(|arg| arg.as_str())).collect::<Vec<&str>>().join(" ");
+    match args.get(depth) {
+        None => MyToolsError::InvalidCommand(format!("'{}' needs one of: {}", command, words.join(", "))),
+        Some(word) => {
+            let suggestion = closest_word(word, &words)
+                .map_or(String::new(), |closest| format!(" (did you mean '{} {}'?)", command, closest));
+            MyToolsError::InvalidCommand(format!("unknown command '{} {}'{}", command, word, suggestion))
+        },
+    }
+}
+
+/// Function to find the word closest to a misspelled one, if close enough to be a typo
+fn closest_word<'a>(word: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(word, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= 2 && *distance < word.len())
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Function to get the Levenshtein distance between two words
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == *cb { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Function to complete the word being typed after the given words, from the specs of the commands of an addon
+///
+/// Candidates are the next words of the commands, or their options if the word starts with '-'
+pub fn complete_command(specs: &[CommandSpec], words: &[&str], current: &str) -> Vec<String> {
+    let candidates = specs.iter().flat_map(|spec| -> Vec<String> {
+        match current.starts_with('-') {
+            true if spec.matches(words) => spec.options.iter().map(|option| format!("--{}", option.name)).collect(),
+            false if spec.path.len() > words.len() && spec.path.iter().zip(words).all(|(word, arg)| word == arg) => vec![spec.path[words.len()].to_string()],
+            _ => Vec::new(),
+        }
+    });
+
+    let mut completions: Vec<String> = Vec::new();
+    for candidate in candidates.filter(|candidate| candidate.starts_with(current)) {
+        if !completions.contains(&candidate) {
+            completions.push(candidate);
+        }
+    }
+    completions
+}
+
+/// Function to format the help message of an addon from the specs of its commands
+///
+/// Consecutive variants sharing the same help message are grouped
+pub fn format_help(keyword: &str, specs: &[CommandSpec]) -> String {
+    let mut groups: Vec<Vec<&CommandSpec>> = Vec::new();
+    for spec in specs {
+        match groups.last_mut() {
+            Some(group) if group[0].help == spec.help => group.push(spec),
+            _ => groups.push(vec![spec]),
+        }
+    }
+
+    let commands_text_message: Vec<String> = groups.iter().map(|group| {
+        // Generate list of command usages
+        let usages = group
+            .iter()
+            .map(|spec| format!("\t\tmy_tools {} {}", keyword, spec.usage()).trim_end().to_string())
+            .collect::<Vec<String>>();
+
+        // Generate the description of the options, once per option
+        let mut options: Vec<&OptionSpec> = Vec::new();
+        for option in group.iter().flat_map(|spec| &spec.options) {
+            if !options.iter().any(|known| known.name == option.name) {
+                options.push(option);
+            }
+        }
+        let options = options
+            .iter()
+            .map(|option| {
+                let usage = option.usage();
+                format!("\t\t    {:<27} {}", usage.trim_start_matches('[').trim_end_matches(']'), option.help)
+            })
+            .collect::<Vec<String>>();
+
+        // Generate the whole command message
+        format!(
+            "\t{}\n{}\n",
+            group[0].help,
+            usages.into_iter().chain(options).collect::<Vec<String>>().join("\n")
+        )
+    }).collect::<Vec<String>>();
+
+    // Generate the help message
+    format!(r#"
 === Addon: {keyword} ===
 
 Usage: {keyword} <COMMAND>
@@ -61,17 +1197,44 @@ Usage: {keyword} <COMMAND>
 Commands:
 {commands}
 "#,
-            keyword = keyword,
-            commands = commands_text_message.join("\n")
-        )
+        keyword = keyword,
+        commands = commands_text_message.join("\n")
+    )
+}
+
+/// Trait designed to be implemented by every addons
+pub trait MyToolsAddon {
+    /// Function to get the help message of the addon
+    fn get_help(&self) -> String {
+        format_help(self.get_keyword(), &self.get_command_specs())
     }
 
-    /// Function to display the help message if the arguments contains "--help" or "-h"
-    fn call_help(&self, args: &Vec<&str>) {
-        if args.len() == 1 && (args[0] == "--help" || args[0] == "-h") {
-            eprintln!("{}", self.get_help());
-            std::process::exit(0); // Exit with success
-        }
+    /// Function to return the help message as a MyToolsError::Help if the arguments end with "--help" or "-h"
+    ///
+    /// Only the commands starting with the words before it are described (e.g. "get --help")
+    fn call_help(&self, args: &[&str]) -> Result<(), MyToolsError> {
+        let words = match args.split_last() {
+            Some((&"--help", words)) | Some((&"-h", words)) => words,
+            _ => return Ok(()),
+        };
+
+        // Commands sharing the most words with the arguments
+        let specs = self.get_command_specs();
+        let common = |spec: &CommandSpec| spec.path.iter().zip(words).take_while(|(word, arg)| word == arg).count();
+        let depth = specs.iter().map(common).max().unwrap_or(0);
+        let help = match depth {
+            0 => self.get_help(),
+            _ => format_help(self.get_keyword(), &specs.into_iter().filter(|spec| common(spec) == depth).collect::<Vec<CommandSpec>>()),
+        };
+        Err(MyToolsError::Help(help))
+    }
+
+    /// Function to parse the arguments and return a MyToolsAddonCommand
+    fn parse(&self, args: &[String]) -> Result<Box<dyn MyToolsAddonCommand>, MyToolsError> {
+        // Get the help message if args end with "--help" or "-h"
+        self.call_help(&args.iter().map(|arg| arg.as_str()).collect::<Vec<&str>>())?;
+
+        parse_command(self.get_keyword(), &self.get_command_specs(), args)
     }
 
     // Functions to implement
@@ -79,11 +1242,8 @@ Commands:
     /// Function to get the keyword that should be used by the user to call the addon
     fn get_keyword(&self) -> &'static str;
 
-    /// Function to list every commands available
-    fn get_list_commands(&self) -> Vec<CommandInputsHelp>;
-
-    /// Function to parse the arguments and return a MyToolsAddonCommand
-    fn parse(&self, args: &[String]) -> Result<Box<dyn MyToolsAddonCommand>, MyToolsError>;
+    /// Function to get the specs of every commands available
+    fn get_command_specs(&self) -> Vec<CommandSpec>;
 }
 
 /// Error type for the addon
@@ -96,7 +1256,13 @@ pub enum MyToolsError {
     /// Error while parsing command
     ParseCommandError(String),
     /// Error while executing command
-    ExecutionCommandError(String)
+    ExecutionCommandError(String),
+    /// The command ran but its answer is negative (e.g. "no"), the program exits with a non-zero status
+    NegativeResult(CommandResult),
+    /// Help was asked instead of a command, with the help message
+    Help(String),
+    /// Error while loading a plugin, or when it does not answer as expected
+    PluginError(String),
 }
 
 impl fmt::Display for MyToolsError {
@@ -106,9 +1272,282 @@ impl fmt::Display for MyToolsError {
             MyToolsError::InvalidCommand(s) => format!("Invalid command: {}", s),
             MyToolsError::ParseCommandError(s) => format!("Error while parsing command: {}", s),
             MyToolsError::ExecutionCommandError(s) => format!("Error while execution command: {}", s),
+            MyToolsError::NegativeResult(s) => s.to_string(),
+            MyToolsError::Help(s) => s.to_string(),
+            MyToolsError::PluginError(s) => format!("Error while loading plugin: {}", s),
         };
         f.write_str(&description)
     }
 }
 
 impl error::Error for MyToolsError {}
+
+// Tests for CommandResult rendering
+#[test]
+fn render_formats() {
+    let result = CommandResult::with_text(CommandValue::object([
+        ("name", "a \"b\", c".into()),
+        ("count", 2u8.into()),
+        ("missing", CommandValue::Null),
+        ("types", vec!["private", "loopback"].into()),
+    ]), "text form");
+
+    assert_eq!(result.render(OutputFormat::Text), "text form");
+    assert_eq!(result.render(OutputFormat::Json), [
+        "{",
+        "  \"name\": \"a \\\"b\\\", c\",",
+        "  \"count\": 2,",
+        "  \"missing\": null,",
+        "  \"types\": [",
+        "    \"private\",",
+        "    \"loopback\"",
+        "  ]",
+        "}",
+    ].join("\n"));
+    assert_eq!(result.render(OutputFormat::Yaml), [
+        "name: a \"b\", c",
+        "count: 2",
+        "missing: null",
+        "types:",
+        "- private",
+        "- loopback",
+    ].join("\n"));
+    assert_eq!(result.render(OutputFormat::Csv), "name,count,missing,types\n\"a \"\"b\"\", c\",2,,private;loopback");
+}
+
+#[test]
+fn render_nested_lists() {
+    let value = CommandValue::List(vec![
+        CommandValue::object([("network", "10.0.0.0/24".into()), ("hosts", vec![1u8, 2u8].into())]),
+        CommandValue::List(Vec::new()),
+    ]);
+    assert_eq!(CommandResult::new(value.clone()).render(OutputFormat::Yaml), [
+        "- network: 10.0.0.0/24",
+        "  hosts:",
+        "  - 1",
+        "  - 2",
+        "- []",
+    ].join("\n"));
+    // Strings which would read as other types are quoted, numbers keep all their digits
+    let value = CommandValue::List(vec!["true".into(), "10".into(), u128::MAX.into()]);
+    assert_eq!(CommandResult::new(value).render(OutputFormat::Yaml), "- 'true'\n- '10'\n- 340282366920938463463374607431768211455");
+    assert_eq!(CommandResult::field("address", "10.0.0.1").to_string(), "10.0.0.1");
+    assert_eq!(CommandResult::field("contains", false).to_string(), "no");
+}
+
+#[test]
+fn json_round_trip() {
+    let value = CommandValue::object([
+        ("text", "a \"b\"\n\u{e9}".into()),
+        ("count", CommandValue::Number("-340282366920938463463374607431768211455".to_string())),
+        ("items", vec![Some(true), None].into()),
+        ("empty", CommandValue::object([])),
+    ]);
+    assert_eq!(CommandValue::from_json(&value.to_json()), Some(value));
+    assert_eq!(CommandValue::from_json(r#" ["\u00e9", {"a" : false}] "#), Some(CommandValue::List(vec![
+        "\u{e9}".into(),
+        CommandValue::object([("a", false.into())]),
+    ])));
+    assert_eq!(CommandValue::from_json("[1, 2"), None);
+    assert_eq!(CommandValue::from_json("1 2"), None);
+}
+
+// Tests for ResultWriter
+#[test]
+fn result_writer_streamed_list() {
+    let write = |format: OutputFormat, count: usize| {
+        let mut output: Vec<u8> = Vec::new();
+        let mut writer = ResultWriter::new(&mut output, format);
+        writer.begin_list().unwrap();
+        for i in 0..count {
+            writer.write_item(&CommandResult::field("index", i)).unwrap();
+        }
+        writer.end_list().unwrap();
+        String::from_utf8(output).unwrap()
+    };
+    assert_eq!(write(OutputFormat::Text, 2), "0\n1\n");
+    assert_eq!(write(OutputFormat::Json, 2), "[\n  {\n    \"index\": 0\n  },\n  {\n    \"index\": 1\n  }\n]\n");
+    assert_eq!(write(OutputFormat::Json, 0), "[]\n");
+    assert_eq!(write(OutputFormat::Yaml, 2), "- index: 0\n- index: 1\n");
+    assert_eq!(write(OutputFormat::Csv, 2), "index\n0\n1\n");
+}
+
+#[test]
+fn output_format_from_str() {
+    assert_eq!("JSON".parse::<OutputFormat>().unwrap(), OutputFormat::Json);
+    assert_eq!("csv".parse::<OutputFormat>().unwrap(), OutputFormat::Csv);
+    assert!("xml".parse::<OutputFormat>().is_err());
+}
+
+// Tests for the command specs
+#[cfg(test)]
+struct SpecTestCommand(String);
+
+#[cfg(test)]
+impl MyToolsAddonCommand for SpecTestCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        Ok(CommandResult::field("parsed", self.0.as_str()))
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        let build: CommandBuilder = |args| Ok(Box::new(SpecTestCommand(format!(
+            "{:?} {:?} {}",
+            args.values("item"),
+            args.number::<u32>("limit")?,
+            args.flag("all")
+        ))));
+        vec![
+            CommandSpec::new("get address", "Get an address", build)
+                .arg(ArgSpec::required("item", ValueType::Unsigned))
+                .option(OptionSpec::value("limit", "N", ValueType::Unsigned, "Maximum"))
+                .option(OptionSpec::flag("all", "Everything")),
+            CommandSpec::new("get address", "Get an address", build)
+                .arg(ArgSpec::required("item", ValueType::Text))
+                .arg(ArgSpec::optional("item", ValueType::Text))
+                .arg(ArgSpec::zero_or_more("item", ValueType::Integer)),
+            CommandSpec::new("get netmask", "Get a netmask", build),
+        ]
+    }
+}
+
+#[cfg(test)]
+fn parse_spec_test(args: &str) -> Result<String, MyToolsError> {
+    let args: Vec<String> = args.split_whitespace().map(|arg| arg.to_string()).collect();
+    parse_command("test", &SpecTestCommand::get_command_specs(), &args)
+        .map(|cmd| cmd.execute().unwrap().to_string())
+}
+
+#[test]
+fn parse_command_variants() {
+    assert_eq!(parse_spec_test("get address 1 --limit 5 --all").unwrap(), r#"["1"] Some(5) true"#);
+    assert_eq!(parse_spec_test("get address --limit=5 -- 1").unwrap(), r#"["1"] Some(5) false"#);
+    // Not an unsigned, or too many arguments for the first variant
+    assert_eq!(parse_spec_test("get address x").unwrap(), r#"["x"] None false"#);
+    assert_eq!(parse_spec_test("get address x y -1 2").unwrap(), r#"["x", "y", "-1", "2"] None false"#);
+    assert_eq!(parse_spec_test("get netmask").unwrap(), "[] None false");
+}
+
+#[test]
+fn parse_command_lines() {
+    let specs: Vec<CommandSpec> = SpecTestCommand::get_command_specs().into_iter().map(CommandSpec::lines).collect();
+    let parse = |args: &str| {
+        let args: Vec<String> = args.split_whitespace().map(|arg| arg.to_string()).collect();
+        parse_command("test", &specs, &args).and_then(|cmd| cmd.execute())
+    };
+    let path = std::env::temp_dir().join(format!("my_tools_lines_{}.txt", std::process::id()));
+    std::fs::write(&path, "1\n\n# comment\nx y\n1 2 3 x\n").unwrap();
+
+    // The words of each line take the place of "-", or follow the arguments
+    let result = parse(&format!("get address - --all --input {}", path.display())).unwrap_err();
+    assert!(matches!(result, MyToolsError::NegativeResult(result) if result == [
+        r#"["1"] None true"#,
+        r#"line 4: Error while parsing command: Unknown option '--all'"#,
+        r#"line 5: Error while parsing command: Unknown option '--all'"#,
+    ].join("\n")));
+    let result = parse(&format!("get address --input={}", path.display())).unwrap_err();
+    assert!(matches!(result, MyToolsError::NegativeResult(result) if result == [
+        r#"["1"] None false"#,
+        r#"["x", "y"] None false"#,
+        r#"line 5: Error while parsing command: Invalid item: 'x'"#,
+    ].join("\n")));
+    std::fs::remove_file(&path).unwrap();
+
+    // "-" as the value of an option is not read as stdin
+    assert_eq!(parse("get address 1 --limit -").unwrap_err().to_string(), "Error while parsing command: Invalid limit: '-'");
+    assert_eq!(parse("get address --input").unwrap_err().to_string(), "Error while parsing command: Missing value for option '--input'");
+}
+
+#[test]
+fn parse_command_errors() {
+    let error = |args: &str| parse_spec_test(args).unwrap_err().to_string();
+    assert_eq!(error("get adress 1"), "Invalid command: unknown command 'test get adress' (did you mean 'test get address'?)");
+    assert_eq!(error("fetch"), "Invalid command: unknown command 'test fetch'");
+    assert_eq!(error("get"), "Invalid command: 'test get' needs one of: address, netmask");
+    assert_eq!(error("get netmask 1"), "Invalid command: wrong arguments for 'test get netmask', expected:\n\tmy_tools test get netmask");
+    assert_eq!(error("get address 1 --limt 5"), "Error while parsing command: Unknown option '--limt' (did you mean '--limit'?)");
+    assert_eq!(error("get address 1 --limit x"), "Error while parsing command: Invalid limit: 'x'");
+    assert_eq!(error("get address 1 --limit"), "Error while parsing command: Missing value for option '--limit'");
+    assert_eq!(error("get address 1 --all=yes"), "Error while parsing command: Option '--all' does not take a value");
+    assert_eq!(error("get address x y z"), "Error while parsing command: Invalid item: 'z'");
+}
+
+#[test]
+fn parse_command_custom_values() {
+    const OCTET: ValueType = ValueType::Custom(|arg| arg.parse::<u8>()
+        .map(parsed_value)
+        .map_err(|_| MyToolsError::ParseCommandError(format!("Invalid octet: '{}'", arg))));
+    let build: CommandBuilder = |args| Ok(Box::new(SpecTestCommand(format!(
+        "{:?} {:?} {:?}",
+        args.parsed_values::<u8>("octet"),
+        args.parsed::<u8>("max"),
+        args.parsed_arg::<u32>("octet").is_err()
+    ))));
+    let specs = vec![
+        CommandSpec::new("octets", "Parse octets", build)
+            .arg(ArgSpec::one_or_more("octet", OCTET))
+            .option(OptionSpec::value("max", "N", OCTET, "Maximum")),
+    ];
+    let parse = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        parse_command("test", &specs, &args).map(|cmd| cmd.execute().unwrap().to_string())
+    };
+    assert_eq!(parse(&["octets", "1", "255", "--max", "7"]).unwrap(), "[1, 255] Some(7) true");
+    assert_eq!(parse(&["octets", "256"]).unwrap_err().to_string(), "Error while parsing command: Invalid octet: '256'");
+}
+
+#[test]
+fn reads_stdin_from_specs() {
+    let reads = |specs: &[CommandSpec], args: &str| {
+        let args: Vec<String> = args.split_whitespace().map(|arg| arg.to_string()).collect();
+        reads_stdin(specs, &args)
+    };
+    let specs = SpecTestCommand::get_command_specs();
+    assert!(!reads(&specs, "get address -"));
+
+    let lines: Vec<CommandSpec> = specs.iter().cloned().map(CommandSpec::lines).collect();
+    assert!(reads(&lines, "get address -"));
+    assert!(reads(&lines, "get address -- -"));
+    assert!(!reads(&lines, "get address 1 --input file"));
+    assert!(!reads(&lines, "get address 1 --limit -"));
+
+    let list = vec![
+        CommandSpec::new("sum", "Sum numbers", specs[0].build)
+            .arg(ArgSpec::zero_or_more("item", ValueType::Text).stdin())
+            .option(OptionSpec::value("more", "N", ValueType::Text, "More numbers").stdin()),
+    ];
+    assert!(reads(&list, "sum 1 -"));
+    assert!(reads(&list, "sum --more -"));
+    assert!(reads(&list, "sum --more=-"));
+    assert!(!reads(&list, "sum 1 2"));
+}
+
+#[test]
+fn complete_command_from_specs() {
+    let specs = SpecTestCommand::get_command_specs();
+    assert_eq!(complete_command(&specs, &[], ""), vec!["get"]);
+    assert_eq!(complete_command(&specs, &["get"], ""), vec!["address", "netmask"]);
+    assert_eq!(complete_command(&specs, &["get"], "n"), vec!["netmask"]);
+    assert_eq!(complete_command(&specs, &["get", "address", "1"], "--"), vec!["--limit", "--all"]);
+    assert!(complete_command(&specs, &["get", "address"], "x").is_empty());
+}
+
+#[test]
+fn format_help_from_specs() {
+    let help = format_help("test", &SpecTestCommand::get_command_specs());
+    assert_eq!(help, r#"
+=== Addon: test ===
+
+Usage: test <COMMAND>
+
+Commands:
+	Get an address
+		my_tools test get address <item> [--limit N] [--all]
+		my_tools test get address <item> [item] [item] ...
+		    --limit N                   Maximum
+		    --all                       Everything
+
+	Get a netmask
+		my_tools test get netmask
+
+"#);
+}
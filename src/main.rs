@@ -1,8 +1,9 @@
 use std::collections::HashMap;
+use std::io::{BufWriter, Write};
 
 use my_tools::MyToolsError;
 use my_tools::MyToolsAddon;
-use my_tools::CommandResult;
+use my_tools::{OutputFormat, ResultWriter};
 
 // Import the addon modules
 mod addons {
@@ -11,21 +12,45 @@ mod addons {
     pub mod ip_network;
 }
 
+// Interactive shell running the addons
+mod shell;
+// Completion scripts of the shells
+mod completions;
+// Addons loaded from shared libraries
+mod plugins;
+
 // Step 2 : Import the addon
 use addons::hello_world::HelloWorldAddon;
 use addons::ip_network::IpNetworkAddon;
 
-/// Function to get the list of enabled addons
-fn get_enabled_addons() ->  Vec<Box<dyn MyToolsAddon>> {
-    // Step 3 : Add the addon to the list of enabled addons
+/// Function to get the list of addons built into the program
+fn get_builtin_addons() ->  Vec<Box<dyn MyToolsAddon>> {
+    // Step 3 : Add the addon to the list of builtin addons
     vec! [
         Box::new(HelloWorldAddon),
         Box::new(IpNetworkAddon),
     ]
 }
 
-/// Function to call the right addon depending on first argument
-fn call_addon(args: Vec<String>, addons: &Vec<Box<dyn MyToolsAddon>>) -> Result<CommandResult, MyToolsError> {
+/// Keywords of the program itself, which addons cannot use
+const RESERVED_KEYWORDS: &[&str] = &["help", "shell", "completions"];
+
+/// Function to add the addons of the plugins directory to the builtin ones
+///
+/// Plugins are only loaded when one of them may be run, listed or completed
+fn add_plugin_addons(addons: &mut Vec<Box<dyn MyToolsAddon>>) {
+    if let Some(directory) = plugins::plugins_dir() {
+        let keywords: Vec<&str> = RESERVED_KEYWORDS
+            .iter()
+            .copied()
+            .chain(addons.iter().map(|addon| addon.get_keyword()))
+            .collect();
+        addons.extend(plugins::load_plugins(&directory, &keywords));
+    }
+}
+
+/// Function to call the right addon depending on first argument, and write its result to the output
+fn call_addon(args: Vec<String>, addons: &[Box<dyn MyToolsAddon>], output: &mut ResultWriter) -> Result<(), MyToolsError> {
     // Get the addon to run
     let addon_to_run = &args[1];
 
@@ -39,25 +64,56 @@ fn call_addon(args: Vec<String>, addons: &Vec<Box<dyn MyToolsAddon>>) -> Result<
     addons.get(addon_to_run.as_str())
         .ok_or(MyToolsError::AddonNotFound(format!("Addon '{}' not found", addon_to_run)))?
         .parse(&args[2..])?
-        .execute()
+        .execute_to(output)
 }
 
+/// Function to run a command and write its result to stdout, a negative answer being written as a result too
+fn run_command(args: Vec<String>, addons: &[Box<dyn MyToolsAddon>], format: OutputFormat) -> Result<(), MyToolsError> {
+    // The result is buffered as it can be streamed item by item
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    let mut output = ResultWriter::new(&mut stdout, format);
+    let result = call_addon(args, addons, &mut output);
+    let _ = stdout.flush();
+    result
+}
+
+/// Function to extract the global "--output <format>" option, given before the addon keyword
+///
+/// An "--output" after the keyword belongs to the addon command, it is left in its arguments
+fn extract_output_format(args: &mut Vec<String>) -> Result<Option<OutputFormat>, MyToolsError> {
+    let arg = match args.get(1) {
+        Some(arg) if arg == "--output" || arg.starts_with("--output=") => args.remove(1),
+        _ => return Ok(None),
+    };
+
+    let format = match arg.strip_prefix("--output=") {
+        Some(format) => format.to_string(),
+        None if args.len() > 1 => args.remove(1),
+        None => return Err(MyToolsError::ParseCommandError("Missing value for --output".to_string())),
+    };
+    format.parse().map(Some)
+}
 
 /// Function to print the usage of the program
 fn print_usage(enabled_addons: &Vec<Box<dyn MyToolsAddon>>) {
     // Print the usage of the program
-    eprintln!("Usage: my_tools <addon> [args]\n");
+    eprintln!("Usage: my_tools [--output text|json|yaml|csv] <addon> [args]");
+    eprintln!("       my_tools [--output text|json|yaml|csv] shell");
+    eprintln!("       my_tools completions bash|zsh|fish|powershell\n");
     eprintln!("Available addons:");
     for addon in enabled_addons {
         eprintln!("  - {}", addon.get_keyword());
     }
     eprintln!("\nUse 'my_tools <addon> --help' to get more information about an addon.");
+    eprintln!("Use 'my_tools shell' to run commands interactively.");
+    if let Some(directory) = plugins::plugins_dir() {
+        eprintln!("Plugins are loaded from '{}'.", directory.display());
+    }
 }
 
-/// Function to check if keywords are unique
+/// Function to check if the keywords of the builtin addons are unique, plugins being checked when loaded
 fn check_keyword_uniqueness(addons: &Vec<Box<dyn MyToolsAddon>>) {
-    let mut keywords: Vec<&str> = Vec::new();
-    keywords.push("help"); // Reserved keyword
+    let mut keywords: Vec<&str> = RESERVED_KEYWORDS.to_vec();
 
     for addon in addons {
         let keyword = addon.get_keyword();
@@ -72,13 +128,31 @@ fn check_keyword_uniqueness(addons: &Vec<Box<dyn MyToolsAddon>>) {
 /// Main function
 fn main() {
     // List of addons
-    let enabled_addons: Vec<Box<dyn MyToolsAddon>> = get_enabled_addons();
+    let mut enabled_addons: Vec<Box<dyn MyToolsAddon>> = get_builtin_addons();
 
     // Check if keywords are unique
     check_keyword_uniqueness(&enabled_addons);
 
     // Get the arguments passed to the program
-    let args: Vec<String> = std::env::args().collect::<Vec<String>>();
+    let mut args: Vec<String> = std::env::args().collect::<Vec<String>>();
+
+    // Get the output format of the results
+    let format = extract_output_format(&mut args);
+
+    // A builtin addon runs without the plugins, which are loaded for any other keyword, the shell, the completions and the usage
+    let is_builtin = format.is_ok() && args.get(1).is_some_and(|keyword| enabled_addons.iter().any(|addon| addon.get_keyword() == keyword));
+    if !is_builtin {
+        add_plugin_addons(&mut enabled_addons);
+    }
+
+    let format = match format {
+        Ok(format) => format.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("/!\\ {}\n", e);
+            print_usage(&enabled_addons);
+            std::process::exit(1); // Exit with error code 1, invalid output format
+        }
+    };
 
     // Test arguments length
     if args.len() < 2 {
@@ -86,13 +160,47 @@ fn main() {
         std::process::exit(1); // Exit with error code 1, not enough arguments
     }
 
-    // Call the right addon
-    match call_addon(args, &enabled_addons) {
-        Ok(res) => println!("{}", res),
+    // Run the interactive shell, or call the right addon
+    let result = match args[1].as_str() {
+        "shell" if args.len() == 2 => shell::run_shell(&enabled_addons, format),
+        "completions" if args.len() == 3 => args[2]
+            .parse::<completions::Shell>()
+            .map(|shell| print!("{}", completions::generate(shell, &enabled_addons))),
+        _ => run_command(args, &enabled_addons, format),
+    };
+
+    match result {
+        Ok(()) => {},
+        Err(MyToolsError::NegativeResult(_)) => {
+            std::process::exit(1); // Exit with error code 1, negative answer
+        },
+        Err(MyToolsError::Help(help)) => {
+            eprintln!("{}", help);
+        },
         Err(e) => {
             eprintln!("/!\\ {}\n", e);
+            if is_builtin {
+                add_plugin_addons(&mut enabled_addons);
+            }
             print_usage(&enabled_addons);
             std::process::exit(1); // Exit with error code 1, addon not found
         }
     }
 }
+
+// Tests for the global options
+#[test]
+fn output_format_before_keyword() {
+    let extract = |args: &[&str]| {
+        let mut args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        extract_output_format(&mut args).map(|format| (format, args))
+    };
+    let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>();
+
+    assert_eq!(extract(&["my_tools", "--output", "json", "ipnet", "info"]).unwrap(), (Some(OutputFormat::Json), args(&["my_tools", "ipnet", "info"])));
+    assert_eq!(extract(&["my_tools", "--output=yaml", "shell"]).unwrap(), (Some(OutputFormat::Yaml), args(&["my_tools", "shell"])));
+    // After the keyword, the option is an argument of the addon
+    assert_eq!(extract(&["my_tools", "greet", "--output", "json"]).unwrap(), (None, args(&["my_tools", "greet", "--output", "json"])));
+    assert!(extract(&["my_tools", "--output"]).is_err());
+    assert!(extract(&["my_tools", "--output", "xml", "ipnet"]).is_err());
+}
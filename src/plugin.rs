@@ -0,0 +1,562 @@
+use std::collections::HashMap;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+use crate::{ArgSpec, Arity, CommandResult, CommandSpec, CommandValue, MyToolsAddon, MyToolsAddonCommand, MyToolsError, OptionSpec, ValueType};
+
+/// Version of the C ABI, increased on every incompatible change of PluginApi or of the JSON it exchanges
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+/// Version of my_tools, plugins must be built against a compatible one (same major, or same minor before 1.0)
+pub const MY_TOOLS_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Symbol of the entry point exported by plugins with export_addon!
+pub const PLUGIN_ENTRY_POINT: &[u8] = b"my_tools_plugin\0";
+
+/// Type of the entry point of plugins, returning the table of their functions
+pub type PluginEntryPoint = unsafe extern "C" fn() -> *const PluginApi;
+
+// Statuses returned by the functions of a plugin, the output being the result or the error message
+const STATUS_OK: u32 = 0;
+const STATUS_NEGATIVE_RESULT: u32 = 1;
+const STATUS_INVALID_COMMAND: u32 = 2;
+const STATUS_PARSE_ERROR: u32 = 3;
+const STATUS_EXECUTION_ERROR: u32 = 4;
+const STATUS_HELP: u32 = 5;
+const STATUS_ADDON_NOT_FOUND: u32 = 6;
+
+/// Table of the functions of a plugin, wrapping its MyToolsAddon and its MyToolsAddonCommand with C types only
+///
+/// Strings are NUL-terminated UTF-8, allocated by the plugin and given back to free_string.
+/// Results and specs are passed as JSON. The first two fields are kept by every ABI version.
+#[repr(C)]
+pub struct PluginApi {
+    pub abi_version: u32,
+    /// Version of my_tools the plugin was built against
+    pub my_tools_version: *const c_char,
+    /// Addon of the plugin, given back to its functions
+    pub addon: *const c_void,
+    pub keyword: unsafe extern "C" fn(addon: *const c_void) -> *mut c_char,
+    pub help: unsafe extern "C" fn(addon: *const c_void) -> *mut c_char,
+    /// Specs of the commands, as a JSON list
+    pub specs: unsafe extern "C" fn(addon: *const c_void) -> *mut c_char,
+    /// Parse the arguments, setting `command` on success and `output` to the error otherwise
+    pub parse: unsafe extern "C" fn(addon: *const c_void, argc: usize, argv: *const *const c_char, command: *mut *mut c_void, output: *mut *mut c_char) -> u32,
+    /// Execute a parsed command, setting `output` to its result or to the error
+    pub execute: unsafe extern "C" fn(command: *mut c_void, output: *mut *mut c_char) -> u32,
+    pub free_command: unsafe extern "C" fn(command: *mut c_void),
+    pub free_string: unsafe extern "C" fn(string: *mut c_char),
+}
+
+/// Macro exporting the addon of a plugin built as a "cdylib", e.g. `my_tools::export_addon!(MyAddon);`
+#[macro_export]
+macro_rules! export_addon {
+    ($addon:expr) => {
+        /// Entry point of the plugin, called once by my_tools when loading it
+        #[no_mangle]
+        pub extern "C" fn my_tools_plugin() -> *const $crate::plugin::PluginApi {
+            $crate::plugin::PluginApi::export(Box::new($addon))
+        }
+    };
+}
+
+impl PluginApi {
+    /// Function to build the table of functions of an addon, for the entry point of its plugin
+    ///
+    /// The table and the addon are never freed, a plugin stays loaded until the process exits
+    pub fn export(addon: Box<dyn MyToolsAddon>) -> *const PluginApi {
+        let api = PluginApi {
+            abi_version: PLUGIN_ABI_VERSION,
+            my_tools_version: concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char,
+            addon: Box::into_raw(Box::new(addon)) as *const c_void,
+            keyword: plugin_keyword,
+            help: plugin_help,
+            specs: plugin_specs,
+            parse: plugin_parse,
+            execute: plugin_execute,
+            free_command: plugin_free_command,
+            free_string: plugin_free_string,
+        };
+        Box::into_raw(Box::new(api))
+    }
+}
+
+// Functions of the plugin side
+
+/// Function to get the addon given back to the functions of a plugin
+///
+/// # Safety
+/// `addon` must be the addon of a PluginApi built by PluginApi::export
+unsafe fn exported_addon<'a>(addon: *const c_void) -> &'a dyn MyToolsAddon {
+    &**(addon as *const Box<dyn MyToolsAddon>)
+}
+
+/// Function to give a string to the other side, which frees it with free_string
+fn into_c_string(value: String) -> *mut c_char {
+    // A string cannot contain NUL characters, they are dropped
+    CString::new(value.replace('\0', "")).unwrap_or_default().into_raw()
+}
+
+/// Function to run the code of an addon, a panic being reported as an error instead of crossing the ABI
+fn catch_panic<T>(run: impl FnOnce() -> Result<T, MyToolsError>) -> Result<T, MyToolsError> {
+    catch_unwind(AssertUnwindSafe(run))
+        .unwrap_or_else(|_| Err(MyToolsError::ExecutionCommandError("The plugin panicked".to_string())))
+}
+
+unsafe extern "C" fn plugin_keyword(addon: *const c_void) -> *mut c_char {
+    // A panic gives an empty keyword, which my_tools rejects
+    let addon = exported_addon(addon);
+    into_c_string(catch_panic(|| Ok(addon.get_keyword().to_string())).unwrap_or_default())
+}
+
+unsafe extern "C" fn plugin_help(addon: *const c_void) -> *mut c_char {
+    let addon = exported_addon(addon);
+    into_c_string(catch_panic(|| Ok(addon.get_help())).unwrap_or_else(|e| e.to_string()))
+}
+
+unsafe extern "C" fn plugin_specs(addon: *const c_void) -> *mut c_char {
+    let addon = exported_addon(addon);
+    into_c_string(catch_panic(|| Ok(encode_specs(&addon.get_command_specs()))).unwrap_or_else(|_| "[]".to_string()))
+}
+
+unsafe extern "C" fn plugin_parse(addon: *const c_void, argc: usize, argv: *const *const c_char, command: *mut *mut c_void, output: *mut *mut c_char) -> u32 {
+    let addon = exported_addon(addon);
+    let args: Vec<String> = (0..argc)
+        .map(|index| CStr::from_ptr(*argv.add(index)).to_string_lossy().into_owned())
+        .collect();
+
+    match catch_panic(|| addon.parse(&args)) {
+        Ok(parsed) => {
+            *command = Box::into_raw(Box::new(parsed)) as *mut c_void;
+            STATUS_OK
+        },
+        Err(e) => {
+            let (status, payload) = encode_error(e);
+            *output = into_c_string(payload);
+            status
+        },
+    }
+}
+
+unsafe extern "C" fn plugin_execute(command: *mut c_void, output: *mut *mut c_char) -> u32 {
+    let command = &*(command as *const Box<dyn MyToolsAddonCommand>);
+    let (status, payload) = match catch_panic(|| command.execute()) {
+        Ok(result) => (STATUS_OK, encode_result(&result)),
+        Err(e) => encode_error(e),
+    };
+    *output = into_c_string(payload);
+    status
+}
+
+unsafe extern "C" fn plugin_free_command(command: *mut c_void) {
+    drop(Box::from_raw(command as *mut Box<dyn MyToolsAddonCommand>));
+}
+
+unsafe extern "C" fn plugin_free_string(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Function to encode a result as JSON
+fn encode_result(result: &CommandResult) -> String {
+    CommandValue::object([("value", result.value().clone()), ("text", result.text().into())]).to_json()
+}
+
+/// Function to encode an error as its status and message (or result for a negative answer)
+fn encode_error(error: MyToolsError) -> (u32, String) {
+    match error {
+        MyToolsError::NegativeResult(result) => (STATUS_NEGATIVE_RESULT, encode_result(&result)),
+        MyToolsError::InvalidCommand(message) => (STATUS_INVALID_COMMAND, message),
+        MyToolsError::ParseCommandError(message) => (STATUS_PARSE_ERROR, message),
+        MyToolsError::ExecutionCommandError(message) => (STATUS_EXECUTION_ERROR, message),
+        MyToolsError::Help(message) => (STATUS_HELP, message),
+        MyToolsError::AddonNotFound(message) => (STATUS_ADDON_NOT_FOUND, message),
+        // Plugins are loaded by the host only
+        MyToolsError::PluginError(message) => (STATUS_EXECUTION_ERROR, message),
+    }
+}
+
+/// Function to encode the specs of the commands as JSON, without their value types and builders
+fn encode_specs(specs: &[CommandSpec]) -> String {
+    let specs = specs.iter().map(|spec| {
+        let args = spec.args.iter().map(|arg| {
+            let arity = match arg.arity {
+                Arity::One => "one",
+                Arity::Optional => "optional",
+                Arity::OneOrMore => "one_or_more",
+                Arity::ZeroOrMore => "zero_or_more",
+            };
+            CommandValue::object([("name", arg.name.into()), ("arity", arity.into()), ("stdin", arg.stdin.into())])
+        });
+        let options = spec.options.iter().map(|option| CommandValue::object([
+            ("name", option.name.into()),
+            ("value_name", option.value_name.into()),
+            ("required", option.required.into()),
+            ("help", option.help.into()),
+            ("stdin", option.stdin.into()),
+        ]));
+        CommandValue::object([
+            ("path", spec.path.clone().into()),
+            ("help", spec.help.as_str().into()),
+            ("args", args.collect::<Vec<CommandValue>>().into()),
+            ("options", options.collect::<Vec<CommandValue>>().into()),
+            ("lines", spec.lines.into()),
+        ])
+    });
+    CommandValue::List(specs.collect()).to_json()
+}
+
+// Functions of the host side
+
+/// Addon of a plugin, calling the functions of its PluginApi
+pub struct PluginAddon {
+    api: &'static PluginApi,
+    keyword: &'static str,
+    /// Specs of the commands, decoded once when the plugin is loaded
+    specs: Vec<CommandSpec>,
+}
+
+/// Command parsed by a plugin, freed by the plugin when dropped
+struct PluginCommand {
+    api: &'static PluginApi,
+    command: *mut c_void,
+}
+
+impl PluginAddon {
+    /// Function to wrap the PluginApi returned by the entry point of a plugin, after checking its versions
+    ///
+    /// # Safety
+    /// `api` must be returned by the entry point of a plugin, which must stay loaded as long as the addon is used
+    pub unsafe fn from_api(api: *const PluginApi) -> Result<Self, MyToolsError> {
+        let api: &'static PluginApi = api.as_ref()
+            .ok_or(MyToolsError::PluginError("The entry point returned no addon".to_string()))?;
+        if api.abi_version != PLUGIN_ABI_VERSION {
+            return Err(MyToolsError::PluginError(format!("Incompatible ABI version {} (expected {})", api.abi_version, PLUGIN_ABI_VERSION)))
+        }
+        if api.my_tools_version.is_null() {
+            return Err(MyToolsError::PluginError("The plugin does not give the version of my_tools it was built against".to_string()))
+        }
+        let version = CStr::from_ptr(api.my_tools_version).to_string_lossy();
+        if !is_compatible_version(&version, MY_TOOLS_VERSION) {
+            return Err(MyToolsError::PluginError(format!("Built against my_tools {}, incompatible with {}", version, MY_TOOLS_VERSION)))
+        }
+
+        let keyword = take_string(api, (api.keyword)(api.addon));
+        if keyword.is_empty() {
+            return Err(MyToolsError::PluginError("The addon has no keyword".to_string()))
+        }
+        let invalid_specs = || MyToolsError::PluginError(format!("Invalid command specs for addon '{}'", keyword));
+        let specs = CommandValue::from_json(&take_string(api, (api.specs)(api.addon))).ok_or_else(invalid_specs)?;
+
+        let mut texts = vec![keyword.as_str()];
+        value_strings(&specs, &mut texts);
+        let store = StringStore::new(texts);
+        let specs = decode_specs(&specs, &store).ok_or_else(invalid_specs)?;
+        Ok(PluginAddon { api, keyword: store.get(&keyword).unwrap_or_default(), specs })
+    }
+}
+
+impl MyToolsAddon for PluginAddon {
+    fn get_help(&self) -> String {
+        // SAFETY: the plugin stays loaded, see from_api
+        unsafe { take_string(self.api, (self.api.help)(self.api.addon)) }
+    }
+
+    fn parse(&self, args: &[String]) -> Result<Box<dyn MyToolsAddonCommand>, MyToolsError> {
+        // The plugin parses its own arguments, help included
+        let args = args
+            .iter()
+            .map(|arg| CString::new(arg.as_str()))
+            .collect::<Result<Vec<CString>, _>>()
+            .map_err(|_| MyToolsError::ParseCommandError("Arguments cannot contain NUL characters".to_string()))?;
+        let argv: Vec<*const c_char> = args.iter().map(|arg| arg.as_ptr()).collect();
+
+        let (mut command, mut output) = (ptr::null_mut(), ptr::null_mut());
+        // SAFETY: the plugin stays loaded, and argv outlives the call
+        unsafe {
+            match (self.api.parse)(self.api.addon, argv.len(), argv.as_ptr(), &mut command, &mut output) {
+                STATUS_OK => Ok(Box::new(PluginCommand { api: self.api, command })),
+                status => Err(decode_error(status, take_string(self.api, output))),
+            }
+        }
+    }
+
+    fn get_keyword(&self) -> &'static str {
+        self.keyword
+    }
+
+    fn get_command_specs(&self) -> Vec<CommandSpec> {
+        self.specs.clone()
+    }
+}
+
+impl MyToolsAddonCommand for PluginCommand {
+    fn execute(&self) -> Result<CommandResult, MyToolsError> {
+        let mut output = ptr::null_mut();
+        // SAFETY: the command is owned by this struct until it is dropped
+        unsafe {
+            let status = (self.api.execute)(self.command, &mut output);
+            let payload = take_string(self.api, output);
+            match status {
+                STATUS_OK => decode_result(&payload),
+                status => Err(decode_error(status, payload)),
+            }
+        }
+    }
+
+    fn get_command_specs() -> Vec<CommandSpec> {
+        // Specs are given by the addon of the plugin
+        Vec::new()
+    }
+}
+
+impl Drop for PluginCommand {
+    fn drop(&mut self) {
+        // SAFETY: the command was set by the parse function of the plugin, and is freed once
+        unsafe { (self.api.free_command)(self.command) }
+    }
+}
+
+/// Function to check that a plugin built against a version of my_tools can be loaded by another one
+fn is_compatible_version(plugin: &str, host: &str) -> bool {
+    let plugin: Vec<&str> = plugin.split('.').collect();
+    let host: Vec<&str> = host.split('.').collect();
+    match (plugin.first(), host.first()) {
+        (Some(&"0"), Some(&"0")) => plugin.get(1).is_some() && plugin.get(1) == host.get(1),
+        (Some(plugin), Some(host)) => plugin == host,
+        _ => false,
+    }
+}
+
+/// Function to copy a string given by a plugin, and give it back to be freed
+///
+/// # Safety
+/// `string` must be null or allocated by the plugin of `api`
+unsafe fn take_string(api: &PluginApi, string: *mut c_char) -> String {
+    if string.is_null() {
+        return String::new()
+    }
+    let value = CStr::from_ptr(string).to_string_lossy().into_owned();
+    (api.free_string)(string);
+    value
+}
+
+/// Function to decode a result encoded by encode_result
+fn decode_result(payload: &str) -> Result<CommandResult, MyToolsError> {
+    let invalid = || MyToolsError::PluginError(format!("Invalid result: {}", payload));
+    match CommandValue::from_json(payload).ok_or_else(invalid)? {
+        CommandValue::Object(mut fields) => match (fields.pop(), fields.pop()) {
+            (Some((text_key, CommandValue::String(text))), Some((value_key, value))) if text_key == "text" && value_key == "value" => {
+                Ok(CommandResult::with_text(value, text))
+            },
+            _ => Err(invalid()),
+        },
+        _ => Err(invalid()),
+    }
+}
+
+/// Function to decode an error encoded by encode_error
+fn decode_error(status: u32, payload: String) -> MyToolsError {
+    match status {
+        STATUS_NEGATIVE_RESULT => decode_result(&payload).map_or_else(|e| e, MyToolsError::NegativeResult),
+        STATUS_INVALID_COMMAND => MyToolsError::InvalidCommand(payload),
+        STATUS_PARSE_ERROR => MyToolsError::ParseCommandError(payload),
+        STATUS_EXECUTION_ERROR => MyToolsError::ExecutionCommandError(payload),
+        STATUS_HELP => MyToolsError::Help(payload),
+        STATUS_ADDON_NOT_FOUND => MyToolsError::AddonNotFound(payload),
+        status => MyToolsError::PluginError(format!("Unknown status {}: {}", status, payload)),
+    }
+}
+
+/// Strings of a plugin (its keyword and the strings of its specs), leaked at once in a single buffer
+/// when the plugin is loaded, as specs need static strings
+struct StringStore {
+    strings: HashMap<String, &'static str>,
+}
+
+impl StringStore {
+    /// Function to store the strings, each one once
+    fn new<'a>(texts: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut texts: Vec<&str> = texts.into_iter().collect();
+        texts.sort_unstable();
+        texts.dedup();
+
+        let buffer: &'static str = Box::leak(texts.concat().into_boxed_str());
+        let mut offset = 0;
+        let strings = texts
+            .into_iter()
+            .map(|text| {
+                let stored = &buffer[offset..offset + text.len()];
+                offset += text.len();
+                (text.to_string(), stored)
+            })
+            .collect();
+        StringStore { strings }
+    }
+
+    /// Function to get the stored copy of a string
+    fn get(&self, text: &str) -> Option<&'static str> {
+        self.strings.get(text).copied()
+    }
+}
+
+/// Function to get every string of a value, to be stored before decoding it
+fn value_strings<'a>(value: &'a CommandValue, texts: &mut Vec<&'a str>) {
+    match value {
+        CommandValue::String(text) => texts.push(text),
+        CommandValue::List(items) => items.iter().for_each(|item| value_strings(item, texts)),
+        CommandValue::Object(fields) => fields.iter().for_each(|(_, value)| value_strings(value, texts)),
+        _ => {},
+    }
+}
+
+/// Function to decode specs encoded by encode_specs, their strings being taken from the store of the plugin
+///
+/// Values are checked by the plugin when it parses the arguments, they are all read as text here
+fn decode_specs(specs: &CommandValue, store: &StringStore) -> Option<Vec<CommandSpec>> {
+    fn field<'a>(value: &'a CommandValue, key: &str) -> Option<&'a CommandValue> {
+        match value {
+            CommandValue::Object(fields) => fields.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+    fn list<'a>(value: &'a CommandValue, key: &str) -> Option<&'a [CommandValue]> {
+        match field(value, key)? {
+            CommandValue::List(items) => Some(items),
+            _ => None,
+        }
+    }
+    let stored = |value: &CommandValue| match value {
+        CommandValue::String(text) => store.get(text),
+        _ => None,
+    };
+    let text = |value: &CommandValue, key: &str| stored(field(value, key)?);
+
+    let specs = match specs {
+        CommandValue::List(specs) => specs,
+        _ => return None,
+    };
+    specs.iter().map(|spec| {
+        let path = list(spec, "path")?
+            .iter()
+            .map(stored)
+            .collect::<Option<Vec<&'static str>>>()?;
+        let mut command = CommandSpec::new("", text(spec, "help")?, |_| {
+            Err(MyToolsError::InvalidCommand("Commands of plugins are parsed by their plugin".to_string()))
+        });
+        command.path = path;
+        // The "--input" option of a command run per line is one of its encoded options
+        command.lines = field(spec, "lines") == Some(&CommandValue::Bool(true));
+
+        for arg in list(spec, "args")? {
+            let arity = match text(arg, "arity")? {
+                "one" => Arity::One,
+                "optional" => Arity::Optional,
+                "one_or_more" => Arity::OneOrMore,
+                "zero_or_more" => Arity::ZeroOrMore,
+                _ => return None,
+            };
+            let stdin = field(arg, "stdin") == Some(&CommandValue::Bool(true));
+            command = command.arg(ArgSpec { name: text(arg, "name")?, arity, value_type: ValueType::Text, stdin });
+        }
+        for option in list(spec, "options")? {
+            command = command.option(OptionSpec {
+                name: text(option, "name")?,
+                value_name: text(option, "value_name"),
+                value_type: ValueType::Text,
+                required: field(option, "required")? == &CommandValue::Bool(true),
+                help: text(option, "help")?,
+                stdin: field(option, "stdin") == Some(&CommandValue::Bool(true)),
+            });
+        }
+        Some(command)
+    }).collect()
+}
+
+// Tests for the C ABI, the addon being exported and loaded in the same process
+#[cfg(test)]
+struct PluginTestAddon;
+
+#[cfg(test)]
+impl MyToolsAddon for PluginTestAddon {
+    fn parse(&self, args: &[String]) -> Result<Box<dyn MyToolsAddonCommand>, MyToolsError> {
+        match args.first().map(|arg| arg.as_str()) {
+            Some("no") => Err(MyToolsError::NegativeResult(CommandResult::with_text(false.into(), "no"))),
+            Some("panic") => panic!("test panic"),
+            _ => {
+                self.call_help(&args.iter().map(|arg| arg.as_str()).collect::<Vec<&str>>())?;
+                crate::parse_command(self.get_keyword(), &self.get_command_specs(), args)
+            },
+        }
+    }
+
+    fn get_keyword(&self) -> &'static str {
+        "test"
+    }
+
+    fn get_command_specs(&self) -> Vec<CommandSpec> {
+        crate::SpecTestCommand::get_command_specs()
+    }
+}
+
+#[test]
+fn plugin_round_trip() {
+    let addon = unsafe { PluginAddon::from_api(PluginApi::export(Box::new(PluginTestAddon))) }.unwrap();
+    let run = |args: &str| {
+        let args: Vec<String> = args.split_whitespace().map(|arg| arg.to_string()).collect();
+        addon.parse(&args).and_then(|cmd| cmd.execute())
+    };
+
+    assert_eq!(addon.get_keyword(), "test");
+    assert_eq!(addon.get_help(), PluginTestAddon.get_help());
+    assert_eq!(run("get address 1 --limit 5").unwrap(), CommandResult::field("parsed", r#"["1"] Some(5) false"#));
+    assert_eq!(run("no"), Err(MyToolsError::NegativeResult(CommandResult::with_text(false.into(), "no"))));
+    assert_eq!(run("get --help"), PluginTestAddon.parse(&["get".to_string(), "--help".to_string()]).map(|_| CommandResult::new(CommandValue::Null)));
+    assert_eq!(run("get address x --limit 5"), Err(MyToolsError::ParseCommandError("Invalid item: 'x'".to_string())));
+    assert_eq!(run("panic"), Err(MyToolsError::ExecutionCommandError("The plugin panicked".to_string())));
+}
+
+#[test]
+fn plugin_command_specs() {
+    let addon = unsafe { PluginAddon::from_api(PluginApi::export(Box::new(PluginTestAddon))) }.unwrap();
+    let usages = |specs: Vec<CommandSpec>| specs.iter().map(|spec| (spec.path.clone(), spec.help.clone(), spec.usage())).collect::<Vec<_>>();
+    assert_eq!(usages(addon.get_command_specs()), usages(PluginTestAddon.get_command_specs()));
+}
+
+#[cfg(test)]
+struct PluginPanicTestAddon;
+
+#[cfg(test)]
+impl MyToolsAddon for PluginPanicTestAddon {
+    fn parse(&self, _args: &[String]) -> Result<Box<dyn MyToolsAddonCommand>, MyToolsError> {
+        Err(MyToolsError::InvalidCommand("no commands".to_string()))
+    }
+
+    fn get_keyword(&self) -> &'static str {
+        panic!("test panic")
+    }
+
+    fn get_command_specs(&self) -> Vec<CommandSpec> {
+        Vec::new()
+    }
+}
+
+#[test]
+fn plugin_invalid_api() {
+    let error = |api: *const PluginApi| unsafe { PluginAddon::from_api(api) }.err().unwrap();
+    assert_eq!(error(ptr::null()), MyToolsError::PluginError("The entry point returned no addon".to_string()));
+    assert_eq!(error(PluginApi::export(Box::new(PluginPanicTestAddon))), MyToolsError::PluginError("The addon has no keyword".to_string()));
+
+    let api = PluginApi::export(Box::new(PluginTestAddon)) as *mut PluginApi;
+    unsafe { (*api).my_tools_version = ptr::null() };
+    assert_eq!(error(api), MyToolsError::PluginError("The plugin does not give the version of my_tools it was built against".to_string()));
+}
+
+#[test]
+fn plugin_versions() {
+    assert!(is_compatible_version("0.1.3", "0.1.0"));
+    assert!(!is_compatible_version("0.2.0", "0.1.0"));
+    assert!(is_compatible_version("1.4.0", "1.0.2"));
+    assert!(!is_compatible_version("2.0.0", "1.0.2"));
+    assert!(!is_compatible_version("", "0.1.0"));
+}
@@ -0,0 +1,129 @@
+use std::path::{Path, PathBuf};
+
+use libloading::{Library, Symbol};
+use my_tools::plugin::{PluginAddon, PluginEntryPoint, PLUGIN_ENTRY_POINT};
+use my_tools::{MyToolsAddon, MyToolsError};
+
+/// Function to get the directory of the plugins, "MY_TOOLS_PLUGINS_DIR" or the data directory of the user
+pub fn plugins_dir() -> Option<PathBuf> {
+    match std::env::var_os("MY_TOOLS_PLUGINS_DIR") {
+        Some(directory) => Some(PathBuf::from(directory)),
+        None => Some(dirs::data_dir()?.join("my_tools").join("plugins")),
+    }
+}
+
+/// Function to load the addons of every plugin of a directory, in the order of their file names
+///
+/// A plugin which cannot be loaded, or whose keyword is already used (by the program, a builtin addon
+/// or a previous plugin), is reported and skipped, the other addons still work
+pub fn load_plugins(directory: &Path, keywords: &[&str]) -> Vec<Box<dyn MyToolsAddon>> {
+    let mut paths: Vec<PathBuf> = match std::fs::read_dir(directory) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|extension| extension == std::env::consts::DLL_EXTENSION))
+            .collect(),
+        // No plugins directory, no plugins
+        Err(_) => return Vec::new(),
+    };
+    paths.sort();
+
+    let mut keywords: Vec<&str> = keywords.to_vec();
+    let mut addons: Vec<Box<dyn MyToolsAddon>> = Vec::new();
+    for path in paths {
+        let addon = load_plugin(&path).and_then(|addon| match keywords.contains(&addon.get_keyword()) {
+            true => Err(MyToolsError::PluginError(format!("{}: keyword '{}' is already used", path.display(), addon.get_keyword()))),
+            false => Ok(addon),
+        });
+        match addon {
+            Ok(addon) => {
+                keywords.push(addon.get_keyword());
+                addons.push(Box::new(addon));
+            },
+            Err(e) => eprintln!("/!\\ {}", e),
+        }
+    }
+    addons
+}
+
+/// Function to load the addon of a plugin, after checking that it was built for this version of my_tools
+pub fn load_plugin(path: &Path) -> Result<PluginAddon, MyToolsError> {
+    // SAFETY: plugins are trusted like the program itself, they are only loaded from the plugins directory
+    unsafe {
+        // The errors of the loader already name the library
+        let library = Library::new(path).map_err(|e| MyToolsError::PluginError(e.to_string()))?;
+        let addon = {
+            let entry_point: Symbol<PluginEntryPoint> = library
+                .get(PLUGIN_ENTRY_POINT)
+                .map_err(|_| MyToolsError::PluginError(format!("{}: not a my_tools plugin, 'my_tools_plugin' is missing", path.display())))?;
+            PluginAddon::from_api(entry_point())
+                .map_err(|e| match e {
+                    MyToolsError::PluginError(message) => MyToolsError::PluginError(format!("{}: {}", path.display(), message)),
+                    e => e,
+                })?
+        };
+
+        // The addon keeps pointers in the library, which stays loaded until the program exits
+        std::mem::forget(library);
+        Ok(addon)
+    }
+}
+
+// Tests for the loading of plugins
+#[cfg(test)]
+/// Function to build the example plugin once, in its own target directory to not wait on the lock of the running cargo
+fn example_plugin() -> &'static Path {
+    static PLUGIN: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+    PLUGIN.get_or_init(|| {
+        let target = Path::new(env!("CARGO_MANIFEST_DIR")).join("target").join("plugin_tests");
+        let status = std::process::Command::new(env!("CARGO"))
+            .args(["build", "--quiet", "--example", "greet_plugin", "--target-dir"])
+            .arg(&target)
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .status()
+            .expect("Failed to run cargo to build the example plugin");
+        assert!(status.success(), "Failed to build the example plugin with 'cargo build --example greet_plugin'");
+        let file = format!("{}greet_plugin{}", std::env::consts::DLL_PREFIX, std::env::consts::DLL_SUFFIX);
+        target.join("debug").join("examples").join(file)
+    })
+}
+
+#[test]
+fn load_example_plugin() {
+    let addon = load_plugin(example_plugin()).expect("Failed to load the example plugin");
+    assert_eq!(addon.get_keyword(), "greet");
+
+    let args = vec!["Bob".to_string(), "--times".to_string(), "2".to_string()];
+    let cmd = addon.parse(&args).expect("Failed to parse command");
+    assert_eq!(cmd.execute().unwrap(), String::from("Greetings, Bob!\nGreetings, Bob!"));
+    assert!(matches!(addon.parse(&["polite".to_string(), "BOB".to_string()]).unwrap().execute(), Err(MyToolsError::NegativeResult(_))));
+}
+
+#[test]
+fn load_plugins_skip_invalid() {
+    let directory = std::env::temp_dir().join(format!("my_tools_plugins_{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(directory.join(format!("invalid.{}", std::env::consts::DLL_EXTENSION)), "not a library").unwrap();
+    std::fs::write(directory.join("notes.txt"), "not a plugin").unwrap();
+
+    assert!(load_plugins(&directory, &[]).is_empty());
+    assert!(load_plugin(&directory.join(format!("invalid.{}", std::env::consts::DLL_EXTENSION))).is_err());
+    assert!(load_plugins(&directory.join("missing"), &[]).is_empty());
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn load_plugins_skip_duplicate_keywords() {
+    let plugin = example_plugin();
+    let file = plugin.file_name().unwrap().to_string_lossy();
+    let directory = std::env::temp_dir().join(format!("my_tools_duplicate_plugins_{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    for copy in ["a", "b"] {
+        std::fs::copy(plugin, directory.join(format!("{}_{}", copy, file))).unwrap();
+    }
+
+    // The first copy is loaded, the second one uses the same keyword
+    let addons = load_plugins(&directory, &["help", "ipnet"]);
+    assert_eq!(addons.iter().map(|addon| addon.get_keyword()).collect::<Vec<&str>>(), vec!["greet"]);
+    assert!(load_plugins(&directory, &["greet"]).is_empty());
+    std::fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,217 @@
+use std::path::PathBuf;
+
+use my_tools::{complete_command, reads_stdin, CommandSpec, MyToolsAddon, MyToolsError, OutputFormat};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::FileHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+use crate::{extract_output_format, run_command};
+
+/// Commands of the shell itself, handled before the addons
+const BUILTINS: &[&str] = &["help", "exit", "quit"];
+
+/// Helper completing the addon keywords and the words and options of their commands
+struct ShellHelper {
+    /// Keyword and command specs of every addon
+    addons: Vec<(&'static str, Vec<CommandSpec>)>,
+}
+
+impl ShellHelper {
+    /// Function to get the completions of the word being typed after the given words
+    fn completions(&self, words: &[&str], current: &str) -> Vec<String> {
+        let keywords = || self.addons.iter().map(|(keyword, _)| keyword.to_string());
+        let candidates: Vec<String> = match words {
+            [] => BUILTINS.iter().map(|builtin| builtin.to_string()).chain(keywords()).collect(),
+            ["help"] => keywords().collect(),
+            [keyword, words @ ..] => match self.addons.iter().find(|(addon, _)| addon == keyword) {
+                Some((_, specs)) => return complete_command(specs, words, current),
+                None => Vec::new(),
+            },
+        };
+        candidates.into_iter().filter(|candidate| candidate.starts_with(current)).collect()
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        // Only the words before the cursor are completed, the last one being the word typed
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map_or(0, |index| index + 1);
+        let words: Vec<&str> = line[..start].split_whitespace().collect();
+        Ok((start, self.completions(&words, &line[start..])))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+/// Function to get the file where the history of the shell is kept, in the data directory of the user
+fn history_file() -> Option<PathBuf> {
+    let directory = dirs::data_dir()?.join("my_tools");
+    std::fs::create_dir_all(&directory).ok()?;
+    Some(directory.join("shell_history"))
+}
+
+/// Function to split a line of the shell into arguments, with the quotes and escapes of a POSIX shell
+fn parse_line(line: &str) -> Result<Vec<String>, MyToolsError> {
+    shell_words::split(line)
+        .map_err(|_| MyToolsError::ParseCommandError("Missing closing quote".to_string()))
+}
+
+/// Function to check that a command of an addon does not read stdin, which is the terminal the shell reads its lines from
+fn check_stdin(args: &[String], addons: &[Box<dyn MyToolsAddon>]) -> Result<(), MyToolsError> {
+    let (keyword, args) = match args {
+        [keyword, args @ ..] => (keyword, args),
+        [] => return Ok(()),
+    };
+    let specs = addons
+        .iter()
+        .find(|addon| addon.get_keyword() == keyword)
+        .map(|addon| addon.get_command_specs())
+        .unwrap_or_default();
+    match reads_stdin(&specs, args) {
+        true => Err(MyToolsError::ParseCommandError("'-' reads stdin, which is not available in the shell (run the command outside of it)".to_string())),
+        false => Ok(()),
+    }
+}
+
+/// Function to print the help message of the shell
+fn print_shell_help(addons: &[Box<dyn MyToolsAddon>]) {
+    eprintln!("Usage: [--output text|json|yaml|csv] <addon> [args]\n");
+    eprintln!("Available addons:");
+    for addon in addons {
+        eprintln!("  - {}", addon.get_keyword());
+    }
+    eprintln!("\nBuiltins:");
+    eprintln!("  help [addon]  Print this message, or the help message of an addon");
+    eprintln!("  exit, quit    Leave the shell (or Ctrl-D)");
+}
+
+/// Function to run the interactive shell, reading a command per line until "exit" or the end of the input
+pub fn run_shell(addons: &[Box<dyn MyToolsAddon>], format: OutputFormat) -> Result<(), MyToolsError> {
+    let helper = ShellHelper {
+        addons: addons.iter().map(|addon| (addon.get_keyword(), addon.get_command_specs())).collect(),
+    };
+    let mut editor: Editor<ShellHelper, FileHistory> = Editor::new()
+        .map_err(|e| MyToolsError::ExecutionCommandError(format!("Cannot start the shell: {}", e)))?;
+    editor.set_helper(Some(helper));
+
+    // The history is optional, the shell still works without a data directory
+    let history = history_file();
+    if let Some(history) = &history {
+        let _ = editor.load_history(history);
+    }
+
+    loop {
+        let line = match editor.readline("my_tools> ") {
+            Ok(line) => line,
+            // Ctrl-C only discards the current line
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("/!\\ {}", e);
+                break;
+            },
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line.as_str());
+        let mut args = match parse_line(&line) {
+            Ok(args) if args.is_empty() => continue,
+            Ok(args) => args,
+            Err(e) => {
+                eprintln!("/!\\ {}", e);
+                continue;
+            },
+        };
+
+        // Builtins
+        match args.iter().map(|arg| arg.as_str()).collect::<Vec<&str>>()[..] {
+            ["exit"] | ["quit"] => break,
+            ["help"] => {
+                print_shell_help(addons);
+                continue;
+            },
+            ["help", keyword] => {
+                match addons.iter().find(|addon| addon.get_keyword() == keyword) {
+                    Some(addon) => eprintln!("{}", addon.get_help()),
+                    None => eprintln!("/!\\ {}", MyToolsError::AddonNotFound(format!("Addon '{}' not found", keyword))),
+                }
+                continue;
+            },
+            _ => {},
+        }
+
+        // Errors are reported without leaving the shell, a negative answer is already written as a result
+        args.insert(0, "my_tools".to_string());
+        let result = extract_output_format(&mut args)
+            .and_then(|line_format| {
+                check_stdin(&args[1..], addons)?;
+                run_command(args, addons, line_format.unwrap_or(format))
+            });
+        match result {
+            Ok(()) | Err(MyToolsError::NegativeResult(_)) => {},
+            Err(MyToolsError::Help(help)) => eprintln!("{}", help),
+            Err(e) => eprintln!("/!\\ {}", e),
+        }
+    }
+
+    if let Some(history) = &history {
+        let _ = editor.save_history(history);
+    }
+    Ok(())
+}
+
+// Tests for the completion of the shell
+#[test]
+fn shell_completions() {
+    use crate::get_builtin_addons;
+
+    let helper = ShellHelper {
+        addons: get_builtin_addons().iter().map(|addon| (addon.get_keyword(), addon.get_command_specs())).collect(),
+    };
+    assert_eq!(helper.completions(&[], "h"), vec!["help", "hello"]);
+    assert_eq!(helper.completions(&["help"], "ip"), vec!["ipnet"]);
+    assert_eq!(helper.completions(&["ipnet", "get"], "n"), vec!["netmask", "network"]);
+    assert_eq!(helper.completions(&["ipnet", "hosts", "10.0.0.0/24"], "--l"), vec!["--limit"]);
+    assert!(helper.completions(&["unknown"], "").is_empty());
+}
+
+// Tests for the parsing of the lines of the shell
+#[test]
+fn shell_parse_line() {
+    assert_eq!(parse_line("ipnet  get address 10.0.0.1/8").unwrap(), vec!["ipnet", "get", "address", "10.0.0.1/8"]);
+    assert_eq!(parse_line("hello 'John Doe' \"a b\" c\\ d").unwrap(), vec!["hello", "John Doe", "a b", "c d"]);
+    assert_eq!(parse_line("").unwrap(), Vec::<String>::new());
+    assert_eq!(parse_line("hello 'John").unwrap_err().to_string(), "Error while parsing command: Missing closing quote");
+}
+
+#[test]
+fn shell_check_stdin() {
+    use crate::get_builtin_addons;
+
+    let check = |line: &str| check_stdin(&parse_line(line).unwrap(), &get_builtin_addons());
+    for line in ["ipnet summarize -", "ipnet get address -", "ipnet exclude 10.0.0.0/8 -", "ipnet free 10.0.0.0/24 --used=-"] {
+        assert_eq!(
+            check(line).unwrap_err().to_string(),
+            "Error while parsing command: '-' reads stdin, which is not available in the shell (run the command outside of it)"
+        );
+    }
+    for line in ["hello -", "ipnet summarize --input nets.txt", "ipnet get address --input=nets.txt", "unknown -", ""] {
+        assert!(check(line).is_ok());
+    }
+}
@@ -0,0 +1,111 @@
+# bash completion for my_tools, generated by 'my_tools completions bash'
+
+_my_tools() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local context="" word opts=""
+
+    if [[ "${prev}" == "--output" ]]; then
+        COMPREPLY=($(compgen -W "text json yaml csv" -- "${cur}"))
+        return
+    fi
+
+    # The context is made of the words of the command typed so far
+    for word in "${COMP_WORDS[@]:1:COMP_CWORD-1}"; do
+        case "${context:+${context} }${word}" in
+            "completions" | \
+            "shell" | \
+            "hello" | \
+            "ipnet" | \
+            "ipnet get" | \
+            "ipnet get address" | \
+            "ipnet get netmask" | \
+            "ipnet get network" | \
+            "ipnet get wildcard" | \
+            "ipnet get binary" | \
+            "ipnet get hex" | \
+            "ipnet get int" | \
+            "ipnet convert" | \
+            "ipnet info" | \
+            "ipnet split" | \
+            "ipnet vlsm" | \
+            "ipnet summarize" | \
+            "ipnet range" | \
+            "ipnet contains" | \
+            "ipnet overlap" | \
+            "ipnet exclude" | \
+            "ipnet intersect" | \
+            "ipnet union" | \
+            "ipnet hosts" | \
+            "ipnet nth" | \
+            "ipnet ptr" | \
+            "ipnet rdns-zones" | \
+            "ipnet classify" | \
+            "ipnet add" | \
+            "ipnet sub" | \
+            "ipnet diff" | \
+            "ipnet next" | \
+            "ipnet prev" | \
+            "ipnet parent" | \
+            "ipnet children" | \
+            "ipnet free" | \
+            "ipnet mapped" | \
+            "ipnet nat64" | \
+            "ipnet 6to4" | \
+            "ipnet teredo" | \
+            "ipnet decode" | \
+            "ipnet eui64" | \
+            "ipnet link-local" | \
+            "ipnet diagnose")
+                context="${context:+${context} }${word}" ;;
+        esac
+    done
+
+    case "${context}" in
+        "") opts="hello ipnet shell completions --output" ;;
+        "completions") opts="bash zsh fish powershell" ;;
+        "ipnet") opts="get convert info split vlsm summarize range contains overlap exclude intersect union hosts nth ptr rdns-zones classify add sub diff next prev parent children free mapped nat64 6to4 teredo decode eui64 link-local diagnose" ;;
+        "ipnet get") opts="address netmask network wildcard binary hex int" ;;
+        "ipnet get address") opts="--input" ;;
+        "ipnet get netmask") opts="--input" ;;
+        "ipnet get network") opts="--input" ;;
+        "ipnet get wildcard") opts="--input" ;;
+        "ipnet get binary") opts="--input" ;;
+        "ipnet get hex") opts="--input" ;;
+        "ipnet get int") opts="--input" ;;
+        "ipnet convert") opts="--input" ;;
+        "ipnet info") opts="--input" ;;
+        "ipnet split") opts="--limit --offset --input --hosts" ;;
+        "ipnet vlsm") opts="--input" ;;
+        "ipnet summarize") opts="--supernet --input" ;;
+        "ipnet range") opts="--input" ;;
+        "ipnet contains") opts="--input" ;;
+        "ipnet overlap") opts="--input" ;;
+        "ipnet exclude") opts="--input" ;;
+        "ipnet intersect") opts="--input" ;;
+        "ipnet union") opts="--input" ;;
+        "ipnet hosts") opts="--include-network-broadcast --limit --offset --step --input" ;;
+        "ipnet nth") opts="--include-network-broadcast --input" ;;
+        "ipnet ptr") opts="--input" ;;
+        "ipnet rdns-zones") opts="--input" ;;
+        "ipnet classify") opts="--input" ;;
+        "ipnet add") opts="--input" ;;
+        "ipnet sub") opts="--input" ;;
+        "ipnet diff") opts="--input" ;;
+        "ipnet next") opts="--input" ;;
+        "ipnet prev") opts="--input" ;;
+        "ipnet parent") opts="--input" ;;
+        "ipnet children") opts="--input" ;;
+        "ipnet free") opts="--used --fit --input" ;;
+        "ipnet mapped") opts="--input" ;;
+        "ipnet nat64") opts="--input" ;;
+        "ipnet 6to4") opts="--input" ;;
+        "ipnet teredo") opts="--input" ;;
+        "ipnet decode") opts="--input" ;;
+        "ipnet eui64") opts="--input" ;;
+        "ipnet link-local") opts="--input" ;;
+        "ipnet diagnose") opts="--input" ;;
+    esac
+    COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+}
+
+complete -F _my_tools my_tools
@@ -0,0 +1,64 @@
+# fish completion for my_tools, generated by 'my_tools completions fish'
+
+# The context is made of the words of the command typed so far
+function __my_tools_context
+    set -l words (commandline -opc)
+    if test "$words[-1]" = '--output'
+        echo -- '--output'
+        return
+    end
+    set -l context ''
+    for word in $words[2..-1]
+        set -l next (string trim -- "$context $word")
+        if contains -- $next 'completions' 'shell' 'hello' 'ipnet' 'ipnet get' 'ipnet get address' 'ipnet get netmask' 'ipnet get network' 'ipnet get wildcard' 'ipnet get binary' 'ipnet get hex' 'ipnet get int' 'ipnet convert' 'ipnet info' 'ipnet split' 'ipnet vlsm' 'ipnet summarize' 'ipnet range' 'ipnet contains' 'ipnet overlap' 'ipnet exclude' 'ipnet intersect' 'ipnet union' 'ipnet hosts' 'ipnet nth' 'ipnet ptr' 'ipnet rdns-zones' 'ipnet classify' 'ipnet add' 'ipnet sub' 'ipnet diff' 'ipnet next' 'ipnet prev' 'ipnet parent' 'ipnet children' 'ipnet free' 'ipnet mapped' 'ipnet nat64' 'ipnet 6to4' 'ipnet teredo' 'ipnet decode' 'ipnet eui64' 'ipnet link-local' 'ipnet diagnose'
+            set context $next
+        end
+    end
+    echo $context
+end
+
+complete -c my_tools -f
+complete -c my_tools -n "test (__my_tools_context) = '--output'" -a 'text json yaml csv'
+complete -c my_tools -n "test (__my_tools_context) = ''" -a 'hello ipnet shell completions --output'
+complete -c my_tools -n "test (__my_tools_context) = 'completions'" -a 'bash zsh fish powershell'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet'" -a 'get convert info split vlsm summarize range contains overlap exclude intersect union hosts nth ptr rdns-zones classify add sub diff next prev parent children free mapped nat64 6to4 teredo decode eui64 link-local diagnose'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet get'" -a 'address netmask network wildcard binary hex int'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet get address'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet get netmask'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet get network'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet get wildcard'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet get binary'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet get hex'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet get int'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet convert'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet info'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet split'" -a '--limit --offset --input --hosts'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet vlsm'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet summarize'" -a '--supernet --input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet range'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet contains'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet overlap'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet exclude'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet intersect'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet union'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet hosts'" -a '--include-network-broadcast --limit --offset --step --input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet nth'" -a '--include-network-broadcast --input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet ptr'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet rdns-zones'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet classify'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet add'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet sub'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet diff'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet next'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet prev'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet parent'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet children'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet free'" -a '--used --fit --input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet mapped'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet nat64'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet 6to4'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet teredo'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet decode'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet eui64'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet link-local'" -a '--input'
+complete -c my_tools -n "test (__my_tools_context) = 'ipnet diagnose'" -a '--input'
@@ -0,0 +1,119 @@
+# PowerShell completion for my_tools, generated by 'my_tools completions powershell'
+
+Register-ArgumentCompleter -Native -CommandName my_tools -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $words = @($commandAst.CommandElements | Select-Object -Skip 1 | ForEach-Object { $_.ToString() })
+    if ($wordToComplete) {
+        $words = @($words | Select-Object -SkipLast 1)
+    }
+
+    # The context is made of the words of the command typed so far
+    $contexts = @(
+        'completions',
+        'shell',
+        'hello',
+        'ipnet',
+        'ipnet get',
+        'ipnet get address',
+        'ipnet get netmask',
+        'ipnet get network',
+        'ipnet get wildcard',
+        'ipnet get binary',
+        'ipnet get hex',
+        'ipnet get int',
+        'ipnet convert',
+        'ipnet info',
+        'ipnet split',
+        'ipnet vlsm',
+        'ipnet summarize',
+        'ipnet range',
+        'ipnet contains',
+        'ipnet overlap',
+        'ipnet exclude',
+        'ipnet intersect',
+        'ipnet union',
+        'ipnet hosts',
+        'ipnet nth',
+        'ipnet ptr',
+        'ipnet rdns-zones',
+        'ipnet classify',
+        'ipnet add',
+        'ipnet sub',
+        'ipnet diff',
+        'ipnet next',
+        'ipnet prev',
+        'ipnet parent',
+        'ipnet children',
+        'ipnet free',
+        'ipnet mapped',
+        'ipnet nat64',
+        'ipnet 6to4',
+        'ipnet teredo',
+        'ipnet decode',
+        'ipnet eui64',
+        'ipnet link-local',
+        'ipnet diagnose'
+    )
+    $context = ''
+    foreach ($word in $words) {
+        $next = "$context $word".Trim()
+        if ($contexts -contains $next) {
+            $context = $next
+        }
+    }
+
+    $completions = switch ($context) {
+        '' { @('hello', 'ipnet', 'shell', 'completions', '--output') }
+        'completions' { @('bash', 'zsh', 'fish', 'powershell') }
+        'ipnet' { @('get', 'convert', 'info', 'split', 'vlsm', 'summarize', 'range', 'contains', 'overlap', 'exclude', 'intersect', 'union', 'hosts', 'nth', 'ptr', 'rdns-zones', 'classify', 'add', 'sub', 'diff', 'next', 'prev', 'parent', 'children', 'free', 'mapped', 'nat64', '6to4', 'teredo', 'decode', 'eui64', 'link-local', 'diagnose') }
+        'ipnet get' { @('address', 'netmask', 'network', 'wildcard', 'binary', 'hex', 'int') }
+        'ipnet get address' { @('--input') }
+        'ipnet get netmask' { @('--input') }
+        'ipnet get network' { @('--input') }
+        'ipnet get wildcard' { @('--input') }
+        'ipnet get binary' { @('--input') }
+        'ipnet get hex' { @('--input') }
+        'ipnet get int' { @('--input') }
+        'ipnet convert' { @('--input') }
+        'ipnet info' { @('--input') }
+        'ipnet split' { @('--limit', '--offset', '--input', '--hosts') }
+        'ipnet vlsm' { @('--input') }
+        'ipnet summarize' { @('--supernet', '--input') }
+        'ipnet range' { @('--input') }
+        'ipnet contains' { @('--input') }
+        'ipnet overlap' { @('--input') }
+        'ipnet exclude' { @('--input') }
+        'ipnet intersect' { @('--input') }
+        'ipnet union' { @('--input') }
+        'ipnet hosts' { @('--include-network-broadcast', '--limit', '--offset', '--step', '--input') }
+        'ipnet nth' { @('--include-network-broadcast', '--input') }
+        'ipnet ptr' { @('--input') }
+        'ipnet rdns-zones' { @('--input') }
+        'ipnet classify' { @('--input') }
+        'ipnet add' { @('--input') }
+        'ipnet sub' { @('--input') }
+        'ipnet diff' { @('--input') }
+        'ipnet next' { @('--input') }
+        'ipnet prev' { @('--input') }
+        'ipnet parent' { @('--input') }
+        'ipnet children' { @('--input') }
+        'ipnet free' { @('--used', '--fit', '--input') }
+        'ipnet mapped' { @('--input') }
+        'ipnet nat64' { @('--input') }
+        'ipnet 6to4' { @('--input') }
+        'ipnet teredo' { @('--input') }
+        'ipnet decode' { @('--input') }
+        'ipnet eui64' { @('--input') }
+        'ipnet link-local' { @('--input') }
+        'ipnet diagnose' { @('--input') }
+        default { @() }
+    }
+    if ($words.Count -gt 0 -and $words[-1] -eq '--output') {
+        $completions = @('text', 'json', 'yaml', 'csv')
+    }
+
+    $completions | Where-Object { $_ -like "$wordToComplete*" } | ForEach-Object {
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }
+}